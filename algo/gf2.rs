@@ -0,0 +1,139 @@
+// Linear systems over GF(2) (day10 part 1)
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Past this many free variables, trying every assignment of them is out
+/// of reach
+pub const MAX_FREE_VARS: usize = 20;
+
+/// Fewest columns whose XOR equals `target`, i.e. the minimum-weight `x`
+/// with `A x = target` over GF(2), where column `j` of `A` has ones in the
+/// rows listed in `columns[j]` (rows past `target.len()` are ignored).
+/// `Ok(None)` if the system is inconsistent, `Err` with the number of free
+/// variables if there are more than `MAX_FREE_VARS`.
+pub fn min_weight_solution(target: &[u8], columns: &[Vec<usize>]) -> Result<Option<usize>, usize> {
+    let num_rows = target.len();
+    let num_cols = columns.len();
+
+    // Augmented matrix: matrix[row][col] = 1 if the column touches the row
+    let mut matrix = vec![vec![0u8; num_cols + 1]; num_rows];
+
+    for (col, rows) in columns.iter().enumerate() {
+        for &row in rows {
+            if row < num_rows {
+                matrix[row][col] = 1;
+            }
+        }
+    }
+
+    for (row, &val) in target.iter().enumerate() {
+        matrix[row][num_cols] = val;
+    }
+
+    // Gaussian elimination to RREF; pivots are (column, row) pairs
+    let mut pivot_row = 0;
+    let mut pivots: Vec<(usize, usize)> = Vec::new();
+
+    for col in 0..num_cols {
+        if pivot_row >= num_rows {
+            break;
+        }
+
+        // Find pivot in current column
+        let mut row = pivot_row;
+        while row < num_rows && matrix[row][col] == 0 {
+            row += 1;
+        }
+
+        if row < num_rows {
+            // Swap rows to bring pivot to position
+            matrix.swap(pivot_row, row);
+
+            // Eliminate all other rows using XOR (GF(2) addition)
+            let pivot = matrix[pivot_row].clone();
+            for (r, row) in matrix.iter_mut().enumerate() {
+                if r != pivot_row && row[col] == 1 {
+                    for (cell, &p) in row[col..].iter_mut().zip(&pivot[col..]) {
+                        *cell ^= p;
+                    }
+                }
+            }
+
+            pivots.push((col, pivot_row));
+            pivot_row += 1;
+        }
+    }
+
+    // Check for inconsistency
+    if matrix[pivot_row..].iter().any(|row| row[num_cols] == 1) {
+        return Ok(None);
+    }
+
+    // Identify free variables
+    let mut is_pivot = vec![false; num_cols];
+    for &(col, _) in &pivots {
+        is_pivot[col] = true;
+    }
+    let free_vars: Vec<usize> = (0..num_cols).filter(|&col| !is_pivot[col]).collect();
+    if free_vars.len() > MAX_FREE_VARS {
+        return Err(free_vars.len());
+    }
+
+    // Try all combinations to find minimum Hamming weight
+    let mut min_weight = usize::MAX;
+
+    for mask in 0..(1u32 << free_vars.len()) {
+        let mut solution = vec![0u8; num_cols];
+
+        // Set free variables based on bitmask
+        for (bit_idx, &var_idx) in free_vars.iter().enumerate() {
+            if (mask >> bit_idx) & 1 == 1 {
+                solution[var_idx] = 1;
+            }
+        }
+
+        // Back-substitute to find pivot variable values
+        for &(pivot_col, pivot_row) in &pivots {
+            let mut val = matrix[pivot_row][num_cols];
+            for &free_var in &free_vars {
+                if matrix[pivot_row][free_var] == 1 {
+                    val ^= solution[free_var];
+                }
+            }
+            solution[pivot_col] = val;
+        }
+
+        let weight = solution.iter().filter(|&&v| v == 1).count();
+        min_weight = min_weight.min(weight);
+    }
+
+    Ok(Some(min_weight))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_weight_solution() {
+        // [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1)
+        let columns = vec![
+            vec![3],
+            vec![1, 3],
+            vec![2],
+            vec![2, 3],
+            vec![0, 2],
+            vec![0, 1],
+        ];
+        assert_eq!(min_weight_solution(&[0, 1, 1, 0], &columns), Ok(Some(2)));
+        assert_eq!(min_weight_solution(&[1], &[vec![0], vec![0]]), Ok(Some(1)));
+        assert_eq!(min_weight_solution(&[1], &[]), Ok(None));
+
+        // One pivot, and a free variable for every other column
+        let same = vec![vec![0]; MAX_FREE_VARS + 1];
+        assert_eq!(min_weight_solution(&[1], &same), Ok(Some(1)));
+        let same = vec![vec![0]; MAX_FREE_VARS + 2];
+        assert_eq!(min_weight_solution(&[1], &same), Err(MAX_FREE_VARS + 1));
+    }
+}
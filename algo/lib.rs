@@ -0,0 +1,14 @@
+// Advent of Code 2025 - solver kernels that need only `core` and `alloc`
+//
+// Pure logic lifted out of the day binaries so it can be embedded where std
+// isn't available: no I/O, no printing, no hashing. `cargo nostd` builds it
+// on its own. Tests still get std through the test harness.
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+pub mod gf2;
+pub mod orientations;
+pub mod ranges;
+pub mod union_find;
@@ -0,0 +1,70 @@
+// Rotations and reflections of polyominoes (day12)
+
+use alloc::vec::Vec;
+
+pub type Point = (i32, i32);
+pub type Shape = Vec<Point>;
+
+/// Translates `shape` so its minimum row and column are zero, with the
+/// cells sorted so equal shapes compare equal
+pub fn normalize(shape: &Shape) -> Shape {
+    let min_row = shape.iter().map(|p| p.0).min().unwrap_or(0);
+    let min_col = shape.iter().map(|p| p.1).min().unwrap_or(0);
+    let mut normalized: Shape = shape
+        .iter()
+        .map(|p| (p.0 - min_row, p.1 - min_col))
+        .collect();
+    normalized.sort();
+    normalized
+}
+
+fn rotate90(shape: &Shape) -> Shape {
+    shape.iter().map(|p| (p.1, -p.0)).collect()
+}
+
+fn reflect(shape: &Shape) -> Shape {
+    shape.iter().map(|p| (p.0, -p.1)).collect()
+}
+
+// Using iterator to generate rotations lazily - avoiding infinite collection issue!
+fn rotations(shape: &Shape) -> impl Iterator<Item = Shape> {
+    let mut current = shape.clone();
+    (0..4).map(move |i| {
+        if i > 0 {
+            current = rotate90(&current);
+        }
+        current.clone()
+    })
+}
+
+/// The distinct normalized orientations of `shape`: 4 rotations of it and 4
+/// of its mirror image, in that order, minus duplicates
+pub fn all_orientations(shape: &Shape) -> Vec<Shape> {
+    let mut orientations: Vec<Shape> = Vec::new();
+
+    // There are at most 8, so a linear scan stands in for a hash set
+    for rotated in rotations(shape).chain(rotations(&reflect(shape))) {
+        let normalized = normalize(&rotated);
+        if !orientations.contains(&normalized) {
+            orientations.push(normalized);
+        }
+    }
+
+    orientations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_orientations() {
+        let square = vec![(0, 0), (0, 1), (1, 0), (1, 1)];
+        let ell = vec![(0, 0), (1, 0), (2, 0), (2, 1)];
+        let skew = vec![(0, 0), (0, 1), (1, 1), (1, 2)];
+
+        assert_eq!(all_orientations(&square).len(), 1);
+        assert_eq!(all_orientations(&ell).len(), 8);
+        assert_eq!(all_orientations(&skew).len(), 4);
+    }
+}
@@ -0,0 +1,46 @@
+// Inclusive integer ranges (day05)
+
+use alloc::vec::Vec;
+use core::ops::Add;
+
+/// Merges overlapping or adjacent inclusive ranges into sorted disjoint ones
+pub fn merge_ranges<T>(ranges: &[(T, T)]) -> Vec<(T, T)>
+where
+    T: Copy + Ord + Add<Output = T> + From<u8>,
+{
+    let mut sorted: Vec<_> = ranges.to_vec();
+    sorted.sort_by_key(|(start, _)| *start);
+
+    let mut merged: Vec<(T, T)> = Vec::new();
+
+    for (start, stop) in sorted {
+        if let Some((_, prev_stop)) = merged.last_mut() {
+            // Only adds one past a stop that is below `start`, so it cannot
+            // overflow
+            if start <= *prev_stop || start == *prev_stop + T::from(1) {
+                // Overlapping or adjacent, merge them
+                *prev_stop = (*prev_stop).max(stop);
+            } else {
+                // No overlap, add new range
+                merged.push((start, stop));
+            }
+        } else {
+            merged.push((start, stop));
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_ranges() {
+        let ranges = [(10, 14), (3, 5), (16, 20), (12, 18), (6, 6)];
+        assert_eq!(merge_ranges(&ranges), vec![(3, 6), (10, 20)]);
+        assert_eq!(merge_ranges::<i64>(&[]), vec![]);
+        assert_eq!(merge_ranges(&[(5, u64::MAX), (1, 4)]), vec![(1, u64::MAX)]);
+    }
+}
@@ -0,0 +1,83 @@
+// Disjoint-set forest with path compression and union by rank (day08)
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            components: n,
+        }
+    }
+
+    /// Number of disjoint sets left
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        if self.parent[x] != x {
+            self.parent[x] = self.find(self.parent[x]); // Path compression
+        }
+        self.parent[x]
+    }
+
+    /// Joins the sets of `x` and `y`; false if they were already one
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        let px = self.find(x);
+        let py = self.find(y);
+
+        if px == py {
+            return false;
+        }
+
+        // Union by rank
+        match self.rank[px].cmp(&self.rank[py]) {
+            Ordering::Less => self.parent[px] = py,
+            Ordering::Greater => self.parent[py] = px,
+            Ordering::Equal => {
+                self.parent[py] = px;
+                self.rank[px] += 1;
+            }
+        }
+        self.components -= 1;
+        true
+    }
+
+    /// Sizes of all sets, largest first
+    pub fn set_sizes(&mut self) -> Vec<usize> {
+        // Roots are element indices, so a counts vector replaces a map
+        let mut counts = vec![0; self.parent.len()];
+        for i in 0..self.parent.len() {
+            counts[self.find(i)] += 1;
+        }
+        let mut sizes: Vec<usize> = counts.into_iter().filter(|&count| count > 0).collect();
+        sizes.sort_by(|a, b| b.cmp(a));
+        sizes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut uf = UnionFind::new(6);
+        assert!(uf.union(0, 1));
+        assert!(uf.union(1, 2));
+        assert!(!uf.union(0, 2));
+        assert!(uf.union(4, 5));
+        assert_eq!(uf.components(), 3);
+        assert_eq!(uf.set_sizes(), vec![3, 2, 1]);
+    }
+}
@@ -0,0 +1,424 @@
+// Mutation-based fuzz driver for the day parsers
+//
+// Runs on stable as an ordinary `#[test]`, no cargo-fuzz needed. Seed inputs
+// are mutated at the byte, line and number level and handed to the parser as
+// raw bytes, invalid UTF-8 included, under `catch_unwind`; a parser may return
+// `Ok` or `Err`, but must never panic. Every panicking input is shrunk with the
+// day's minimizer units and written to a crash directory outside the source
+// tree. Fixtures committed to the day's fixture directory are replayed before
+// fuzzing, so past crashes stay covered as regression tests.
+//
+// FUZZ_ITERATIONS (per seed, default 500) and FUZZ_SEED tune a run;
+// FUZZ_CRASH_DIR overrides where new crashes go (default: $TMPDIR/aoc-fuzz).
+// With FUZZ_MINIMIZE=<file>, a day's fuzz test instead shrinks that crashing
+// file with the day's units and writes the reproducer next to it as
+// `<file>.min.txt`; a fixed crash is shrunk while the parser still rejects it
+// the same way:
+//
+//     FUZZ_MINIMIZE=$PWD/crash.txt cargo test -p day05 fuzz -- --nocapture
+
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+use crate::minimize::{self, Lines, Units};
+
+const DEFAULT_ITERATIONS: usize = 500;
+const DEFAULT_SEED: u64 = 0x2025_1201;
+
+// Tokens that are meaningful to at least one day's format
+const DICTIONARY: &[&str] = &[
+    "\n", "\n\n", ",", "-", ":", ": ", " ", "x", "#", ".", "@", "^", "S", "+", "*", "L", "R", "[",
+    "]", "(", ")", "{", "}", "é", "\u{feff}",
+];
+
+const INTERESTING_NUMBERS: &[&str] = &[
+    "",
+    "0",
+    "1",
+    "-1",
+    "2147483648",
+    "9223372036854775807",
+    "-9223372036854775808",
+    "99999999999999999999999999",
+];
+
+thread_local! {
+    static FUZZING: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Deterministic splitmix64, so a failing run can be reproduced from FUZZ_SEED
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform-ish value in `0..n`; `n` must be non-zero
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Crash {
+    pub input: Vec<u8>,
+    pub location: String,
+}
+
+pub struct Fuzzer {
+    fixtures: PathBuf,
+    crashes: PathBuf,
+    seeds: Vec<Vec<u8>>,
+    iterations: usize,
+    units: &'static [&'static dyn Units],
+    rng: Rng,
+}
+
+impl Fuzzer {
+    /// `fixtures` is where committed crashing inputs are replayed from,
+    /// usually `concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz")`
+    pub fn new(fixtures: impl Into<PathBuf>) -> Self {
+        let iterations = env_number("FUZZ_ITERATIONS").unwrap_or(DEFAULT_ITERATIONS as u64);
+        let seed = env_number("FUZZ_SEED").unwrap_or(DEFAULT_SEED);
+        let fixtures: PathBuf = fixtures.into();
+        Self {
+            crashes: crash_dir(&fixtures),
+            fixtures,
+            seeds: Vec::new(),
+            iterations: iterations as usize,
+            units: &[],
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn seed(mut self, input: &str) -> Self {
+        self.seeds.push(input.as_bytes().to_vec());
+        self
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// The minimizer passes for crashing inputs, usually the day's `UNITS`;
+    /// plain lines when none given
+    pub fn units(mut self, units: &'static [&'static dyn Units]) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Replays committed fixtures, then fuzzes every seed. Panics with a
+    /// summary if any input made `parse` panic, after saving the new ones.
+    pub fn run<T, E: Debug>(mut self, parse: impl Fn(&[u8]) -> Result<T, E>) {
+        install_hook();
+
+        if let Some(path) = std::env::var_os("FUZZ_MINIMIZE") {
+            return self.reproduce(&parse, Path::new(&path));
+        }
+
+        let mut crashes: Vec<Crash> = load_fixtures(&self.fixtures)
+            .into_iter()
+            .filter_map(|input| check(&parse, &input))
+            .collect();
+        let replayed = crashes.len();
+
+        for seed_idx in 0..self.seeds.len() {
+            for _ in 0..self.iterations {
+                let input = self.mutate(seed_idx);
+                if let Some(mut crash) = check(&parse, &input) {
+                    // One reproducer per panic site is enough
+                    if !crashes.iter().any(|c| c.location == crash.location) {
+                        crash.input = self.shrink(&parse, &crash);
+                        save_crash(&self.crashes, &crash.input);
+                        crashes.push(crash);
+                    }
+                }
+            }
+        }
+
+        if !crashes.is_empty() {
+            let sites: Vec<&str> = crashes.iter().map(|c| c.location.as_str()).collect();
+            panic!(
+                "parser panicked on {} input(s) ({} replayed from {}, new ones saved to {}): {}",
+                crashes.len(),
+                replayed,
+                self.fixtures.display(),
+                self.crashes.display(),
+                sites.join("; ")
+            );
+        }
+    }
+
+    fn shrink<T, E>(&self, parse: impl Fn(&[u8]) -> Result<T, E>, crash: &Crash) -> Vec<u8> {
+        minimize::minimize(&crash.input, self.passes(), |candidate| {
+            check(&parse, candidate).is_some_and(|c| c.location == crash.location)
+        })
+    }
+
+    /// Shrinks the crashing input in `path` and writes the reproducer next
+    /// to it, for crashes found elsewhere (CI logs, bug reports, ...). A
+    /// committed fixture whose panic has since been fixed is shrunk while
+    /// the parser still rejects it with the same error, numbers aside, so
+    /// it keeps covering the same error path.
+    fn reproduce<T, E: Debug>(&self, parse: impl Fn(&[u8]) -> Result<T, E>, path: &Path) {
+        let input =
+            fs::read(path).unwrap_or_else(|err| panic!("cannot read {}: {err}", path.display()));
+        let out = path.with_extension("min.txt");
+        let write = |fails: &mut dyn FnMut(&[u8]) -> bool| {
+            minimize::write_reproducer(&out, &input, self.passes(), fails)
+                .unwrap_or_else(|err| panic!("cannot write {}: {err}", out.display()))
+        };
+
+        let (minimal, failure) = match check(&parse, &input) {
+            Some(crash) => (
+                write(&mut |candidate| {
+                    check(&parse, candidate).is_some_and(|c| c.location == crash.location)
+                }),
+                format!("panicking at {}", crash.location),
+            ),
+            None => {
+                let rejection = parse(&input)
+                    .err()
+                    .map(|err| error_kind(&err))
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "{} is accepted by the parser; shrink it with a predicate of its own",
+                            path.display()
+                        )
+                    });
+                (
+                    write(&mut |candidate| {
+                        check(&parse, candidate).is_none()
+                            && parse(candidate).err().map(|err| error_kind(&err)).as_ref()
+                                == Some(&rejection)
+                    }),
+                    format!("rejected with {rejection}"),
+                )
+            }
+        };
+        eprintln!(
+            "{} bytes -> {} bytes, {}: {}",
+            input.len(),
+            minimal.len(),
+            failure,
+            out.display()
+        );
+    }
+
+    /// The minimizer passes for this day; plain lines when none were given
+    fn passes(&self) -> &[&dyn Units] {
+        match self.units {
+            [] => &[&Lines],
+            units => units,
+        }
+    }
+
+    fn mutate(&mut self, seed_idx: usize) -> Vec<u8> {
+        let mut bytes = self.seeds[seed_idx].clone();
+        let rounds = 1 + self.rng.below(4);
+
+        for _ in 0..rounds {
+            match self.rng.below(9) {
+                0 => flip_byte(&mut bytes, &mut self.rng),
+                1 => insert_token(&mut bytes, &mut self.rng),
+                2 => delete_span(&mut bytes, &mut self.rng),
+                3 => truncate(&mut bytes, &mut self.rng),
+                4 => with_lines(&mut bytes, |lines| {
+                    let i = self.rng.below(lines.len());
+                    lines.remove(i);
+                }),
+                5 => with_lines(&mut bytes, |lines| {
+                    let i = self.rng.below(lines.len());
+                    lines.insert(i, lines[i].clone());
+                }),
+                6 => with_lines(&mut bytes, |lines| {
+                    let (i, j) = (self.rng.below(lines.len()), self.rng.below(lines.len()));
+                    lines.swap(i, j);
+                }),
+                7 => replace_number(&mut bytes, &mut self.rng),
+                _ => {
+                    // Splice a line from another seed
+                    let other = &self.seeds[self.rng.below(self.seeds.len())];
+                    let donor: Vec<&[u8]> = minimize::non_empty_lines(other).collect();
+                    if !donor.is_empty() {
+                        let line = donor[self.rng.below(donor.len())].to_vec();
+                        with_lines(&mut bytes, |lines| {
+                            let i = self.rng.below(lines.len() + 1);
+                            lines.insert(i, line);
+                        });
+                    }
+                }
+            }
+        }
+
+        bytes
+    }
+}
+
+/// Runs `parse` on `input`, returning the panic site if it panicked
+pub fn check<T, E>(parse: impl Fn(&[u8]) -> Result<T, E>, input: &[u8]) -> Option<Crash> {
+    install_hook();
+    FUZZING.with(|f| *f.borrow_mut() = Some(String::new()));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _ = parse(input);
+    }));
+    let location = FUZZING.with(|f| f.borrow_mut().take()).unwrap_or_default();
+
+    result.err().map(|_| Crash {
+        input: input.to_vec(),
+        location,
+    })
+}
+
+fn install_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let fuzzing = FUZZING.with(|f| {
+                let mut slot = f.borrow_mut();
+                match slot.as_mut() {
+                    Some(location) => {
+                        *location = info
+                            .location()
+                            .map(|l| format!("{}:{}", l.file(), l.line()))
+                            .unwrap_or_default();
+                        true
+                    }
+                    None => false,
+                }
+            });
+            // Stay quiet for expected fuzz panics, report everything else
+            if !fuzzing {
+                previous(info);
+            }
+        }));
+    });
+}
+
+fn env_number(name: &str) -> Option<u64> {
+    std::env::var(name).ok()?.parse().ok()
+}
+
+fn load_fixtures(dir: &Path) -> Vec<Vec<u8>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| Some(e.ok()?.path())).collect();
+    paths.sort();
+    paths
+        .iter()
+        .filter_map(|path| fs::read(path).ok())
+        .collect()
+}
+
+/// New crashes go under FUZZ_CRASH_DIR or the system temp dir, one
+/// subdirectory per crate, so that `cargo test` never writes to the source
+/// tree; copy a reproducer into the fixture directory to keep it
+fn crash_dir(fixtures: &Path) -> PathBuf {
+    let root = std::env::var_os("FUZZ_CRASH_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::temp_dir().join("aoc-fuzz"));
+    let day = fixtures
+        .parent()
+        .and_then(Path::file_name)
+        .unwrap_or_default();
+    root.join(day)
+}
+
+/// A parse error with every run of digits replaced by `N`, so that errors
+/// differing only in line numbers or values compare equal
+fn error_kind(err: &impl Debug) -> String {
+    let mut kind = String::new();
+    let mut in_number = false;
+    for c in format!("{err:?}").chars() {
+        if !c.is_ascii_digit() {
+            kind.push(c);
+        } else if !in_number {
+            kind.push('N');
+        }
+        in_number = c.is_ascii_digit();
+    }
+    kind
+}
+
+fn save_crash(dir: &Path, input: &[u8]) {
+    // FNV-1a keeps crash file names stable across runs
+    let hash = input.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x100_0000_01b3)
+    });
+    let _ = fs::create_dir_all(dir);
+    let _ = fs::write(dir.join(format!("crash-{hash:016x}.txt")), input);
+}
+
+fn flip_byte(bytes: &mut [u8], rng: &mut Rng) {
+    if !bytes.is_empty() {
+        let i = rng.below(bytes.len());
+        bytes[i] = match rng.below(3) {
+            0 => rng.below(256) as u8,
+            1 => b"0123456789"[rng.below(10)],
+            _ => DICTIONARY[rng.below(DICTIONARY.len())].as_bytes()[0],
+        };
+    }
+}
+
+fn insert_token(bytes: &mut Vec<u8>, rng: &mut Rng) {
+    let token = DICTIONARY[rng.below(DICTIONARY.len())].as_bytes();
+    let at = rng.below(bytes.len() + 1);
+    bytes.splice(at..at, token.iter().copied());
+}
+
+fn delete_span(bytes: &mut Vec<u8>, rng: &mut Rng) {
+    if !bytes.is_empty() {
+        let start = rng.below(bytes.len());
+        let len = 1 + rng.below(8.min(bytes.len() - start));
+        bytes.drain(start..start + len);
+    }
+}
+
+fn truncate(bytes: &mut Vec<u8>, rng: &mut Rng) {
+    let at = rng.below(bytes.len() + 1);
+    bytes.truncate(at);
+}
+
+fn with_lines(bytes: &mut Vec<u8>, edit: impl FnOnce(&mut Vec<Vec<u8>>)) {
+    let mut lines: Vec<Vec<u8>> = bytes.split(|&b| b == b'\n').map(<[u8]>::to_vec).collect();
+    edit(&mut lines);
+    *bytes = lines.join(&b'\n');
+}
+
+fn replace_number(bytes: &mut Vec<u8>, rng: &mut Rng) {
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i].is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            runs.push(start..i);
+        } else {
+            i += 1;
+        }
+    }
+
+    if !runs.is_empty() {
+        let run = runs.swap_remove(rng.below(runs.len()));
+        let value = INTERESTING_NUMBERS[rng.below(INTERESTING_NUMBERS.len())];
+        bytes.splice(run, value.bytes());
+    }
+}
@@ -0,0 +1,122 @@
+// Fast non-cryptographic hashing for memo tables
+//
+// The multiply-rotate scheme used by rustc ("FxHash"). It has no HashDoS
+// resistance, which is fine for puzzle inputs, and is much cheaper than
+// std's SipHash for the small integer and short string keys the days use.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasherDefault, Hasher};
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    #[inline]
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0u8; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+pub type FxHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
+pub type FxHashSet<T> = HashSet<T, FxBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::BuildHasher;
+
+    fn hash(f: impl FnOnce(&mut FxHasher)) -> u64 {
+        let mut hasher = FxHasher::default();
+        f(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_words() {
+        // One rotate-xor-multiply step per word, from zero
+        assert_eq!(hash(|h| h.write_u64(1)), SEED);
+        assert_eq!(hash(|h| h.write_u8(1)), SEED);
+        assert_eq!(
+            hash(|h| h.write_u64(2)),
+            hash(|h| h.write(&2u64.to_le_bytes()))
+        );
+
+        // Bytes go in 8 at a time, with the tail zero-padded
+        let bytes = b"abcdefghij";
+        let words = hash(|h| {
+            h.write_u64(u64::from_le_bytes(*b"abcdefgh"));
+            h.write_u64(u64::from_le_bytes(*b"ij\0\0\0\0\0\0"));
+        });
+        assert_eq!(hash(|h| h.write(bytes)), words);
+        assert_ne!(hash(|h| h.write(b"ab")), hash(|h| h.write(b"ba")));
+    }
+
+    #[test]
+    fn test_maps() {
+        // Unlike std's RandomState, every build hasher hashes alike
+        let key = (3usize, 4usize);
+        assert_eq!(
+            FxBuildHasher::default().hash_one(key),
+            FxBuildHasher::default().hash_one(key)
+        );
+
+        let mut memo: FxHashMap<(&[u8], u8), i64> = FxHashMap::default();
+        memo.insert((b"svr", 0), 1);
+        memo.insert((b"svr", 1), 2);
+        assert_eq!(memo.get(&(&b"svr"[..], 1)), Some(&2));
+
+        let seen: FxHashSet<usize> = [1, 2, 2, 3].into_iter().collect();
+        assert_eq!(seen.len(), 3);
+    }
+}
@@ -0,0 +1,33 @@
+// JSON output: just string quoting, for the runner's dashboard and the
+// day01 trace export, which write their few fields by hand
+
+use std::fmt::Write;
+
+/// `text` as a quoted JSON string
+pub fn string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            ch if (ch as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", ch as u32);
+            }
+            ch => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string() {
+        assert_eq!(string("a\"b\\\n\u{1}"), "\"a\\\"b\\\\\\n\\u0001\"");
+        assert_eq!(string("é"), "\"é\"");
+    }
+}
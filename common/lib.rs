@@ -0,0 +1,14 @@
+// Advent of Code - the shared core under every day, year crate and the runner
+
+pub mod fuzz;
+pub mod hash;
+pub mod json;
+pub mod minimize;
+#[cfg(all(unix, target_pointer_width = "64"))]
+mod mmap;
+pub mod params;
+pub mod scan;
+pub mod snapshot;
+pub mod solution;
+pub mod svg;
+pub mod uint;
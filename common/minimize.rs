@@ -0,0 +1,189 @@
+// Delta-debugging test-case minimizer
+//
+// An input is seen as a list of removable units (lines, ranges, edges, ...)
+// defined per day through the `Units` trait. `minimize` runs ddmin over each
+// unit kind in turn, keeping a removal whenever the failure predicate still
+// holds, until no pass can shrink the input any further.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// How an input breaks down into independently removable units
+pub trait Units {
+    /// Number of removable units in `input`
+    fn count(&self, input: &[u8]) -> usize;
+
+    /// Rebuilds `input` keeping only the units at the (ascending) `kept` indices
+    fn keep(&self, input: &[u8], kept: &[usize]) -> Vec<u8>;
+}
+
+/// The non-empty lines of `input`, which need not be valid UTF-8
+pub fn non_empty_lines(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+    input.split(|&b| b == b'\n').filter(|line| !line.is_empty())
+}
+
+/// Tells, unit by unit in input order, whether the unit is among the
+/// ascending `kept` indices; a merge over them, so `keep` stays linear
+pub fn keeper(kept: &[usize]) -> impl FnMut() -> bool + '_ {
+    let mut kept = kept.iter().copied().peekable();
+    let mut index = 0;
+    move || {
+        let keep = kept.next_if_eq(&index).is_some();
+        index += 1;
+        keep
+    }
+}
+
+/// Every non-empty line is a unit; the default for line-per-record formats
+pub struct Lines;
+
+impl Units for Lines {
+    fn count(&self, input: &[u8]) -> usize {
+        non_empty_lines(input).count()
+    }
+
+    fn keep(&self, input: &[u8], kept: &[usize]) -> Vec<u8> {
+        let lines: Vec<&[u8]> = non_empty_lines(input).collect();
+        kept.iter()
+            .flat_map(|&i| [lines[i], b"\n"])
+            .flatten()
+            .copied()
+            .collect()
+    }
+}
+
+/// Shrinks `input` while `fails` keeps returning true for it. Passes over
+/// `units` are repeated until none of them removes anything.
+pub fn minimize(
+    input: &[u8],
+    units: &[&dyn Units],
+    mut fails: impl FnMut(&[u8]) -> bool,
+) -> Vec<u8> {
+    let mut current = input.to_vec();
+
+    loop {
+        let before = current.len();
+        for unit in units {
+            let count = unit.count(&current);
+            let kept = ddmin(count, |kept| fails(&unit.keep(&current, kept)));
+            if kept.len() < count {
+                current = unit.keep(&current, &kept);
+            }
+        }
+        if current.len() >= before {
+            return current;
+        }
+    }
+}
+
+/// A failure predicate for differential tests: `input` fails when `a` and
+/// `b` give different answers for it
+pub fn disagree<A: PartialEq>(
+    a: impl Fn(&[u8]) -> A,
+    b: impl Fn(&[u8]) -> A,
+) -> impl FnMut(&[u8]) -> bool {
+    move |input| a(input) != b(input)
+}
+
+/// Minimizes, then writes the reproducer to `path`
+pub fn write_reproducer(
+    path: &Path,
+    input: &[u8],
+    units: &[&dyn Units],
+    fails: impl FnMut(&[u8]) -> bool,
+) -> io::Result<Vec<u8>> {
+    let minimal = minimize(input, units, fails);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, &minimal)?;
+    Ok(minimal)
+}
+
+/// Complement-based ddmin over unit indices `0..n`: returns a 1-minimal
+/// subset of indices for which `fails` still holds
+pub fn ddmin(n: usize, mut fails: impl FnMut(&[usize]) -> bool) -> Vec<usize> {
+    let mut current: Vec<usize> = (0..n).collect();
+    let mut granularity = 2;
+
+    while current.len() >= 2 {
+        let chunk = current.len().div_ceil(granularity);
+        let reduced = (0..current.len()).step_by(chunk).find_map(|start| {
+            let end = (start + chunk).min(current.len());
+            let candidate: Vec<usize> = current[..start]
+                .iter()
+                .chain(&current[end..])
+                .copied()
+                .collect();
+            fails(&candidate).then_some(candidate)
+        });
+
+        match reduced {
+            Some(candidate) => {
+                current = candidate;
+                granularity = (granularity - 1).max(2);
+            }
+            None if granularity >= current.len() => break,
+            None => granularity = (granularity * 2).min(current.len()),
+        }
+    }
+
+    if current.len() == 1 && fails(&[]) {
+        current.clear();
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimize_lines() {
+        let input: String = (1..=40).map(|i| format!("{i}\n")).collect();
+        // Fails whenever both 7 and 23 are present
+        let fails = |s: &[u8]| {
+            let has = |line: &[u8]| non_empty_lines(s).any(|l| l == line);
+            has(b"7") && has(b"23")
+        };
+
+        assert_eq!(minimize(input.as_bytes(), &[&Lines], fails), b"7\n23\n");
+    }
+
+    #[test]
+    fn test_disagree() {
+        // A sum that skips the last line is right unless that line is nonzero
+        let sum = |s: &[u8]| -> u32 {
+            non_empty_lines(s)
+                .map(|l| std::str::from_utf8(l).unwrap().parse::<u32>().unwrap())
+                .sum()
+        };
+        let buggy = |s: &[u8]| -> u32 {
+            let lines: Vec<&[u8]> = non_empty_lines(s).collect();
+            lines[..lines.len().saturating_sub(1)]
+                .iter()
+                .map(|l| std::str::from_utf8(l).unwrap().parse::<u32>().unwrap())
+                .sum()
+        };
+        let fails = disagree(sum, buggy);
+        assert_eq!(minimize(b"4\n0\n7\n0\n", &[&Lines], fails), b"7\n");
+    }
+
+    #[test]
+    fn test_write_reproducer() {
+        let path = std::env::temp_dir().join(format!("minimize-{}/crash.txt", std::process::id()));
+        let fails = |s: &[u8]| s.contains(&b'x');
+        let minimal = write_reproducer(&path, b"ab\ncxd\n\nef\n", &[&Lines], fails).unwrap();
+        assert_eq!(minimal, b"cxd\n");
+        assert_eq!(fs::read(&path).unwrap(), minimal);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_keeper() {
+        let mut keep = keeper(&[1, 2, 5]);
+        let kept: Vec<bool> = (0..7).map(|_| keep()).collect();
+        assert_eq!(kept, [false, true, true, false, false, true, false]);
+    }
+}
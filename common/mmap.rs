@@ -0,0 +1,75 @@
+// Read-only memory maps of whole files, straight over the C library
+//
+// Only built on 64-bit Unix, where `off_t` is an `i64` and the protection
+// and flag values below are the same on Linux and the BSDs. Everywhere else
+// `scan::read_input` reads the file into a buffer instead.
+
+use std::ffi::c_void;
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::ptr;
+use std::slice;
+
+const PROT_READ: i32 = 1;
+const MAP_PRIVATE: i32 = 2;
+
+extern "C" {
+    fn mmap(
+        addr: *mut c_void,
+        len: usize,
+        prot: i32,
+        flags: i32,
+        fd: i32,
+        offset: i64,
+    ) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> i32;
+}
+
+/// A private, read-only mapping of the first `len` bytes of a file. The
+/// file must not be truncated while it is mapped
+pub struct Mapping {
+    ptr: *mut c_void,
+    len: usize,
+}
+
+// SAFETY: the mapping is never written through, so sharing it is like
+// sharing a `&[u8]`
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    /// `None` if `file` can't be mapped (it is empty, a pipe, ...), so the
+    /// caller can read it instead
+    pub fn new(file: &File, len: usize) -> Option<Mapping> {
+        if len == 0 {
+            return None;
+        }
+        // SAFETY: a fresh mapping chosen by the kernel aliases nothing, and
+        // MAP_FAILED is checked before the pointer is used
+        let ptr = unsafe {
+            mmap(
+                ptr::null_mut(),
+                len,
+                PROT_READ,
+                MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        (ptr as isize != -1).then_some(Mapping { ptr, len })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        // SAFETY: `ptr` is a live, readable mapping of `len` bytes until drop
+        unsafe { slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // SAFETY: unmaps exactly what `new` mapped, once
+        unsafe {
+            munmap(self.ptr, self.len);
+        }
+    }
+}
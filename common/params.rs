@@ -0,0 +1,241 @@
+// Per-day tunable constants
+//
+// Each day declares its defaults, which can then be overridden from an
+// `aoc.toml` file (one `[dayNN]` table per day) and from `--param key=value`
+// command line arguments, in that order of precedence.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+const CONFIG_FILE: &str = "aoc.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    UnknownKeys(Vec<String>),
+    Malformed(String),
+    Invalid { key: String, value: String },
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::UnknownKeys(keys) => write!(f, "unknown parameter(s): {}", keys.join(", ")),
+            ParamError::Malformed(entry) => write!(f, "malformed parameter: {entry}"),
+            ParamError::Invalid { key, value } => write!(f, "invalid value for {key}: {value}"),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+// Lets the days' `Result<_, String>` entry points use `?` on parameters
+impl From<ParamError> for String {
+    fn from(err: ParamError) -> Self {
+        err.to_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Params {
+    values: BTreeMap<&'static str, String>,
+}
+
+impl Params {
+    /// Defaults overridden by `aoc.toml` (searched upwards from the working
+    /// directory) and then by `--param key=value` arguments.
+    pub fn load(day: &str, defaults: &[(&'static str, &str)]) -> Result<Self, ParamError> {
+        Self::load_with(day, defaults, std::env::args().skip(1))
+    }
+
+    /// Like `load`, with `--param` arguments from `args` rather than the
+    /// command line, for a runner that picks which day they apply to
+    pub fn load_with(
+        day: &str,
+        defaults: &[(&'static str, &str)],
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<Self, ParamError> {
+        let config = find_config().and_then(|path| fs::read_to_string(path).ok());
+        Self::from_sources(day, defaults, config.as_deref(), args)
+    }
+
+    pub fn from_sources(
+        day: &str,
+        defaults: &[(&'static str, &str)],
+        config: Option<&str>,
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<Self, ParamError> {
+        let mut params = Self {
+            values: defaults
+                .iter()
+                .map(|&(key, value)| (key, value.to_string()))
+                .collect(),
+        };
+
+        let mut overrides = match config {
+            Some(config) => parse_config(config, day)?,
+            None => Vec::new(),
+        };
+        overrides.extend(parse_args(args)?);
+
+        let mut unknown = Vec::new();
+        for (key, value) in overrides {
+            match params.values.get_mut(key.as_str()) {
+                Some(slot) => *slot = value,
+                None if !unknown.contains(&key) => unknown.push(key),
+                None => {}
+            }
+        }
+
+        if unknown.is_empty() {
+            Ok(params)
+        } else {
+            Err(ParamError::UnknownKeys(unknown))
+        }
+    }
+
+    pub fn raw(&self, key: &str) -> &str {
+        self.values
+            .get(key)
+            .unwrap_or_else(|| panic!("parameter {key} has no default"))
+    }
+
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<T, ParamError> {
+        let value = self.raw(key);
+        value.parse().map_err(|_| ParamError::Invalid {
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// Every key and its value, in key order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+    }
+
+    /// Comma-separated list value, e.g. `via=dac,fft`
+    pub fn list(&self, key: &str) -> Vec<&str> {
+        self.raw(key)
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+fn find_config() -> Option<std::path::PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .find(|path| Path::new(path).is_file())
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Vec<(String, String)>, ParamError> {
+    let mut overrides = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let entry = if arg == "--param" {
+            args.next()
+                .ok_or_else(|| ParamError::Malformed("--param needs key=value".to_string()))?
+        } else if let Some(entry) = arg.strip_prefix("--param=") {
+            entry.to_string()
+        } else {
+            continue;
+        };
+
+        let (key, value) = entry
+            .split_once('=')
+            .ok_or_else(|| ParamError::Malformed(entry.clone()))?;
+        overrides.push((key.trim().to_string(), value.trim().to_string()));
+    }
+
+    Ok(overrides)
+}
+
+/// Minimal TOML subset: `[table]` headers and `key = value` lines, where
+/// values are bare words/numbers or double-quoted strings.
+fn parse_config(config: &str, day: &str) -> Result<Vec<(String, String)>, ParamError> {
+    let mut overrides = Vec::new();
+    let mut in_day = false;
+
+    for line in config.lines() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(table) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_day = table.trim() == day;
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| ParamError::Malformed(line.to_string()))?;
+        if in_day {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            overrides.push((key.trim().to_string(), value.to_string()));
+        }
+    }
+
+    Ok(overrides)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, ch) in line.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULTS: &[(&str, &str)] = &[("start", "50"), ("via", "dac,fft")];
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_precedence() {
+        let config = "[day02]\nstart = 1\n\n[day01]\nstart = 7 # comment\nvia = \"a,b\"\n";
+        let params = Params::from_sources(
+            "day01",
+            DEFAULTS,
+            Some(config),
+            args(&["--param", "start=9"]),
+        )
+        .unwrap();
+
+        assert_eq!(params.get::<i32>("start"), Ok(9));
+        assert_eq!(params.list("via"), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_unknown_and_invalid() {
+        let err = Params::from_sources("day01", DEFAULTS, None, args(&["--param=size=3"]));
+        assert_eq!(
+            err.unwrap_err(),
+            ParamError::UnknownKeys(vec!["size".to_string()])
+        );
+
+        let params = Params::from_sources("day01", DEFAULTS, None, args(&["--param=start=x"]));
+        assert!(params.unwrap().get::<i32>("start").is_err());
+    }
+}
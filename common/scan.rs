@@ -0,0 +1,415 @@
+// Byte-level input scanning
+//
+// Inputs are mapped into memory (or read) once as a single byte buffer, or
+// streamed line by line in fixed-size chunks, and parsed in place: integers straight from ASCII digits
+// with overflow checks, fields as sub-slices, and grids as a strided view
+// over the buffer at one byte per cell.
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, Read};
+use std::ops::Deref;
+use std::path::Path;
+
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// A whole input file as one byte buffer: a read-only memory map where the
+/// platform has one, else the file read into a `Vec`
+pub struct Input {
+    #[cfg(all(unix, target_pointer_width = "64"))]
+    mapped: Option<crate::mmap::Mapping>,
+    read: Vec<u8>,
+}
+
+impl Deref for Input {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        #[cfg(all(unix, target_pointer_width = "64"))]
+        if let Some(mapped) = &self.mapped {
+            return mapped.bytes();
+        }
+        &self.read
+    }
+}
+
+impl AsRef<[u8]> for Input {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+/// Maps a whole input file into memory, falling back to reading it into one
+/// buffer (sized up front from its metadata) if it can't be mapped
+pub fn read_input(path: impl AsRef<Path>) -> io::Result<Input> {
+    let mut file = File::open(path)?;
+    let len = file.metadata().map(|m| m.len() as usize).unwrap_or(0);
+
+    #[cfg(all(unix, target_pointer_width = "64"))]
+    if let Some(mapped) = crate::mmap::Mapping::new(&file, len) {
+        return Ok(Input {
+            mapped: Some(mapped),
+            read: Vec::new(),
+        });
+    }
+
+    let mut read = Vec::with_capacity(len + 1);
+    file.read_to_end(&mut read)?;
+    Ok(Input {
+        #[cfg(all(unix, target_pointer_width = "64"))]
+        mapped: None,
+        read,
+    })
+}
+
+/// Streams `path` in fixed-size chunks, calling `f` on every line without
+/// holding more than one chunk (plus a partial line) in memory
+pub fn for_each_line<E>(
+    path: impl AsRef<Path>,
+    mut f: impl FnMut(&[u8]) -> Result<(), E>,
+) -> io::Result<Result<(), E>> {
+    let mut file = File::open(path)?;
+    let mut buf = vec![0u8; CHUNK_SIZE];
+    let mut filled = 0;
+
+    loop {
+        if filled == buf.len() {
+            // A single line longer than the buffer: grow to fit it
+            buf.resize(buf.len() * 2, 0);
+        }
+        let read = file.read(&mut buf[filled..])?;
+        let eof = read == 0;
+        filled += read;
+
+        let mut start = 0;
+        while let Some(end) = buf[start..filled].iter().position(|&b| b == b'\n') {
+            if let Err(err) = f(strip_cr(&buf[start..start + end])) {
+                return Ok(Err(err));
+            }
+            start += end + 1;
+        }
+
+        if eof {
+            if start < filled {
+                if let Err(err) = f(strip_cr(&buf[start..filled])) {
+                    return Ok(Err(err));
+                }
+            }
+            return Ok(Ok(()));
+        }
+
+        buf.copy_within(start..filled, 0);
+        filled -= start;
+    }
+}
+
+/// Lossy text form of a byte slice, for error messages
+pub fn show(bytes: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(bytes)
+}
+
+fn strip_cr(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Lines like `str::lines`: split on `\n`, drop a trailing `\r` and the empty
+/// piece after a final newline
+pub fn lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    bytes
+        .split(|&b| b == b'\n')
+        .filter(move |_| !bytes.is_empty())
+        .map(strip_cr)
+}
+
+/// Runs of non-whitespace bytes, like `str::split_whitespace`
+pub fn words(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    bytes
+        .split(|b| b.is_ascii_whitespace())
+        .filter(|word| !word.is_empty())
+}
+
+/// Groups of consecutive non-empty lines, separated by blank lines
+pub fn blocks(bytes: &[u8]) -> Vec<Vec<&[u8]>> {
+    let mut blocks = vec![Vec::new()];
+    for line in lines(bytes) {
+        match (line.is_empty(), blocks.last_mut()) {
+            (true, Some(current)) if !current.is_empty() => blocks.push(Vec::new()),
+            (false, Some(current)) => current.push(line),
+            _ => {}
+        }
+    }
+    blocks.retain(|block| !block.is_empty());
+    blocks
+}
+
+/// Splits at the first `delim`, like `str::split_once`
+pub fn split_once(bytes: &[u8], delim: u8) -> Option<(&[u8], &[u8])> {
+    let at = bytes.iter().position(|&b| b == delim)?;
+    Some((&bytes[..at], &bytes[at + 1..]))
+}
+
+/// Integer types that can be parsed from ASCII digits with overflow checks
+pub trait Int: Copy {
+    const SIGNED: bool;
+    const ZERO: Self;
+
+    fn push_digit(self, digit: u8, negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_int {
+    ($signed:expr => $($t:ty),*) => {$(
+        impl Int for $t {
+            const SIGNED: bool = $signed;
+            const ZERO: Self = 0;
+
+            #[inline]
+            fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                let shifted = self.checked_mul(10)?;
+                if negative {
+                    shifted.checked_sub(digit as Self)
+                } else {
+                    shifted.checked_add(digit as Self)
+                }
+            }
+        }
+    )*};
+}
+
+impl_int!(false => u8, u16, u32, u64, u128, usize);
+impl_int!(true => i8, i16, i32, i64, i128, isize);
+
+/// Parses the whole slice as an integer, with an optional sign like
+/// `str::parse` (`-` only for signed types). `None` on junk or overflow.
+pub fn parse<T: Int>(bytes: &[u8]) -> Option<T> {
+    let mut scanner = Scanner::new(bytes);
+    let value = scanner.int()?;
+    scanner.is_empty().then_some(value)
+}
+
+/// Cursor over a byte slice
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    pub fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    pub fn rest(&self) -> &'a [u8] {
+        &self.bytes[self.pos..]
+    }
+
+    /// Consumes `byte` if it is next
+    pub fn eat(&mut self, byte: u8) -> bool {
+        let matched = self.peek() == Some(byte);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    /// Like `eat`, as an `Option` for use with `?`
+    pub fn expect(&mut self, byte: u8) -> Option<()> {
+        self.eat(byte).then_some(())
+    }
+
+    pub fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(|b| b == b' ' || b == b'\t') {
+            self.pos += 1;
+        }
+    }
+
+    /// Everything up to `delim` (consumed, not returned), or the rest
+    pub fn take_until(&mut self, delim: u8) -> &'a [u8] {
+        let rest = self.rest();
+        match rest.iter().position(|&b| b == delim) {
+            Some(at) => {
+                self.pos += at + 1;
+                &rest[..at]
+            }
+            None => {
+                self.pos = self.bytes.len();
+                rest
+            }
+        }
+    }
+
+    /// Optionally signed integer; leaves the cursor untouched on failure
+    pub fn int<T: Int>(&mut self) -> Option<T> {
+        let start = self.pos;
+        let negative = T::SIGNED && self.eat(b'-');
+        if !negative {
+            self.eat(b'+');
+        }
+
+        let digits_start = self.pos;
+        let mut value = T::ZERO;
+        while let Some(b @ b'0'..=b'9') = self.peek() {
+            match value.push_digit(b - b'0', negative) {
+                Some(v) => value = v,
+                None => {
+                    self.pos = start;
+                    return None;
+                }
+            }
+            self.pos += 1;
+        }
+
+        if self.pos == digits_start {
+            self.pos = start;
+            return None;
+        }
+        Some(value)
+    }
+}
+
+/// Rectangular grid of one byte per cell, stored row-major with a fixed
+/// stride; either a view into the input buffer or an owned copy
+#[derive(Debug, Clone)]
+pub struct Grid<B> {
+    cells: B,
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<'a> Grid<&'a [u8]> {
+    /// Views `bytes` in place; every line must have the same width
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, String> {
+        let bytes = bytes.trim_ascii_end();
+        let width = bytes
+            .iter()
+            .position(|&b| b == b'\n')
+            .unwrap_or(bytes.len());
+        let crlf = width > 0 && bytes[width - 1] == b'\r';
+        let (width, stride) = if crlf {
+            (width - 1, width + 1)
+        } else {
+            (width, width + 1)
+        };
+        if width == 0 {
+            return Err("empty grid".to_string());
+        }
+
+        // Rows must sit exactly `stride` apart for the indexing to hold
+        let rows: Vec<&[u8]> = bytes.split(|&b| b == b'\n').collect();
+        let height = rows.len();
+        for (row, line) in rows.iter().enumerate() {
+            let last = row + 1 == height;
+            let line = if crlf && !last {
+                line.strip_suffix(b"\r").unwrap_or(b"")
+            } else {
+                line
+            };
+            if line.len() != width {
+                return Err(format!("row {} is not {} cells wide", row + 1, width));
+            }
+        }
+
+        Ok(Self {
+            cells: bytes,
+            width,
+            height,
+            stride,
+        })
+    }
+
+    pub fn to_owned(&self) -> Grid<Vec<u8>> {
+        Grid {
+            cells: self.cells.to_vec(),
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+        }
+    }
+}
+
+impl<B: AsRef<[u8]>> Grid<B> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    pub fn get(&self, row: usize, col: usize) -> u8 {
+        self.cells.as_ref()[row * self.stride + col]
+    }
+
+    /// Neighbour lookup that returns `None` off the grid
+    #[inline]
+    pub fn get_offset(&self, row: usize, col: usize, dr: isize, dc: isize) -> Option<u8> {
+        let row = row.checked_add_signed(dr).filter(|&r| r < self.height)?;
+        let col = col.checked_add_signed(dc).filter(|&c| c < self.width)?;
+        Some(self.get(row, col))
+    }
+
+    pub fn row(&self, row: usize) -> &[u8] {
+        let start = row * self.stride;
+        &self.cells.as_ref()[start..start + self.width]
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        (0..self.height)
+            .flat_map(move |row| (0..self.width).map(move |col| (row, col, self.get(row, col))))
+    }
+}
+
+impl<B: AsMut<[u8]>> Grid<B> {
+    #[inline]
+    pub fn set(&mut self, row: usize, col: usize, value: u8) {
+        self.cells.as_mut()[row * self.stride + col] = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ints() {
+        assert_eq!(parse::<i64>(b"-9223372036854775808"), Some(i64::MIN));
+        assert_eq!(parse::<i64>(b"9223372036854775808"), None);
+        assert_eq!(parse::<u32>(b"+42"), Some(42));
+        assert_eq!(parse::<u32>(b"-1"), None);
+        assert_eq!(parse::<u8>(b""), None);
+        assert_eq!(parse::<u8>(b"1x"), None);
+    }
+
+    #[test]
+    fn test_read_input() {
+        let path = std::env::temp_dir().join(format!("aoc-scan-{}.txt", std::process::id()));
+        std::fs::write(&path, b"12\n34\n").unwrap();
+        let input = read_input(&path).unwrap();
+        assert_eq!(&*input, b"12\n34\n");
+
+        // An empty file can't be mapped, and reads as nothing instead
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(&*read_input(&path).unwrap(), b"");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_grid_view() {
+        let grid = Grid::parse(b"ab\r\ncd\r\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid.row(1), b"cd");
+        assert_eq!(grid.get_offset(0, 0, 1, 1), Some(b'd'));
+        assert_eq!(grid.get_offset(0, 0, -1, 0), None);
+        assert!(Grid::parse(b"ab\nc\n").is_err());
+    }
+}
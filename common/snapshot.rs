@@ -0,0 +1,167 @@
+// Snapshot tests for rendered output
+//
+// A rendering is compared with a file committed next to the test; on a
+// mismatch the test fails with a line diff of the two. With AOC_BLESS=1 the
+// file is rewritten with the new rendering instead, to be reviewed in the
+// commit like any other change:
+//
+//     AOC_BLESS=1 cargo test -p day04 test_svg
+
+use std::fs;
+use std::path::Path;
+
+/// Set to 1 to rewrite snapshots instead of checking them
+pub const BLESS: &str = "AOC_BLESS";
+
+/// Unchanged lines shown around each change in a diff
+const CONTEXT: usize = 2;
+
+enum Edit<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Shortest line edit from `expected` to `actual`, via their longest common
+/// subsequence
+fn edits<'a>(expected: &[&'a str], actual: &[&'a str]) -> Vec<Edit<'a>> {
+    let (n, m) = (expected.len(), actual.len());
+    // lcs[i][j]: longest common subsequence of expected[i..] and actual[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut edits = Vec::with_capacity(n.max(m));
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            edits.push(Edit::Same(expected[i]));
+            (i, j) = (i + 1, j + 1);
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            edits.push(Edit::Removed(expected[i]));
+            i += 1;
+        } else {
+            edits.push(Edit::Added(actual[j]));
+            j += 1;
+        }
+    }
+    edits
+}
+
+/// Unified-style line diff: `-` lines only in `expected`, `+` lines only in
+/// `actual`, each group of changes under an `@@ -line +line @@` header with
+/// `CONTEXT` unchanged lines around it. Empty if the two are equal
+pub fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let edits = edits(&expected, &actual);
+
+    let changed: Vec<usize> = (0..edits.len())
+        .filter(|&k| !matches!(edits[k], Edit::Same(_)))
+        .collect();
+    let shown = |k: usize| {
+        changed
+            .iter()
+            .any(|&c| c.saturating_sub(CONTEXT) <= k && k <= c + CONTEXT)
+    };
+
+    let mut out = String::new();
+    let (mut line_expected, mut line_actual) = (1, 1);
+    let mut in_hunk = false;
+    for (k, edit) in edits.iter().enumerate() {
+        if shown(k) {
+            if !in_hunk {
+                out.push_str(&format!("@@ -{line_expected} +{line_actual} @@\n"));
+                in_hunk = true;
+            }
+            let (sign, line) = match edit {
+                Edit::Same(line) => (' ', line),
+                Edit::Removed(line) => ('-', line),
+                Edit::Added(line) => ('+', line),
+            };
+            out.push_str(&format!("{sign} {line}\n"));
+        } else {
+            in_hunk = false;
+        }
+        match edit {
+            Edit::Same(_) => (line_expected, line_actual) = (line_expected + 1, line_actual + 1),
+            Edit::Removed(_) => line_expected += 1,
+            Edit::Added(_) => line_actual += 1,
+        }
+    }
+    out
+}
+
+/// Checks `actual` against the snapshot file at `path`, panicking with a line
+/// diff if they differ. With AOC_BLESS=1 the file is (re)written instead
+pub fn assert_snapshot(path: impl AsRef<Path>, actual: &str) {
+    let path = path.as_ref();
+    if std::env::var(BLESS).is_ok_and(|bless| bless == "1") {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).unwrap_or_else(|err| panic!("{}: {err}", dir.display()));
+        }
+        fs::write(path, actual).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+        return;
+    }
+
+    let expected = fs::read_to_string(path).unwrap_or_else(|err| {
+        panic!(
+            "{}: {err}; rerun with {BLESS}=1 to create it",
+            path.display()
+        )
+    });
+    if expected != actual {
+        let diff = diff(&expected, actual);
+        panic!(
+            "{} doesn't match the rendering (- snapshot, + now); \
+             rerun with {BLESS}=1 to accept it\n{}",
+            path.display(),
+            if diff.is_empty() {
+                "(only line endings differ)\n"
+            } else {
+                &diff
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff("a\nb\n", "a\nb\n"), "");
+        assert_eq!(
+            diff(
+                "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n",
+                "1\n2\n3\nfour\n5\n6\n7\n8\n9\n10\n11\n"
+            ),
+            "@@ -2 +2 @@\n  2\n  3\n- 4\n+ four\n  5\n  6\n@@ -9 +9 @@\n  9\n  10\n+ 11\n"
+        );
+        assert_eq!(diff("x\n", ""), "@@ -1 +1 @@\n- x\n");
+    }
+
+    #[test]
+    fn test_assert_snapshot() {
+        let path = std::env::temp_dir().join(format!("aoc-snapshot-{}.txt", std::process::id()));
+        fs::write(&path, "a\nb\n").unwrap();
+        assert_snapshot(&path, "a\nb\n");
+        let mismatch = std::panic::catch_unwind(|| assert_snapshot(&path, "a\nc\n"));
+        fs::remove_file(&path).unwrap();
+
+        let message = mismatch.unwrap_err();
+        let message = message.downcast_ref::<String>().unwrap();
+        assert!(
+            message.ends_with("@@ -1 +1 @@\n  a\n- b\n+ c\n"),
+            "{message}"
+        );
+    }
+}
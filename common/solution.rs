@@ -0,0 +1,163 @@
+// Solutions as the runner sees them
+//
+// A year crate lists its days as `Solution`s in a `Year`. The `aoc` runner and
+// the C ABI find every (year, day) through those tables instead of naming the
+// days themselves, so a new day or year only has to be listed once.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+
+use crate::minimize::Units;
+use crate::params::Params;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    UnknownDay,
+    UnknownPart,
+    Input(String),
+    Panic,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::UnknownDay => write!(f, "no such day"),
+            SolveError::UnknownPart => write!(f, "no such part"),
+            SolveError::Input(reason) => write!(f, "invalid input: {reason}"),
+            SolveError::Panic => write!(f, "solver panicked"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Draws an input as an SVG document
+pub type Draw = fn(input: &[u8], params: &Params) -> Result<String, String>;
+
+/// Generates a random input of `size` of the day's own units (boxes, tiles,
+/// grid cells) from `seed`
+pub type Generate = fn(size: usize, seed: u64) -> Vec<u8>;
+
+/// One day of one year
+pub struct Solution {
+    pub day: u32,
+    /// Parts the day has; some last days have only one
+    pub parts: u32,
+    /// The day's crate directory, which holds its input.txt and test_input.txt
+    pub dir: &'static str,
+    /// The day's `SOLVER_VERSION`, bumped whenever a change can alter its
+    /// answers so that cached ones are recomputed
+    pub version: &'static str,
+    pub defaults: &'static [(&'static str, &'static str)],
+    pub units: &'static [&'static dyn Units],
+    /// The day's parser alone, which accepts or rejects an input
+    pub validate: fn(input: &[u8]) -> Result<(), String>,
+    /// The algorithm, in a line, for the generated report
+    pub approach: &'static str,
+    /// A drawing of the input for the dashboard, for the spatial days
+    pub svg: Option<Draw>,
+    /// Random inputs of growing size, for timing how the solver scales
+    pub generate: Option<Generate>,
+    /// Computes `part`, which is known to exist, for `input`
+    pub solve: fn(part: u32, input: &[u8], params: &Params) -> Result<String, String>,
+}
+
+/// An answer as text, for `Solution::solve`
+pub fn text(answer: Result<impl ToString, String>) -> Result<String, String> {
+    answer.map(|answer| answer.to_string())
+}
+
+impl Solution {
+    /// The params table name, e.g. `day07`
+    pub fn name(&self) -> String {
+        format!("day{:02}", self.day)
+    }
+
+    pub fn input_path(&self) -> PathBuf {
+        PathBuf::from(self.dir).join("input.txt")
+    }
+
+    /// The puzzle's example, which also seeds the day's fuzz test
+    pub fn seed_path(&self) -> PathBuf {
+        PathBuf::from(self.dir).join("test_input.txt")
+    }
+
+    /// Just the defaults, as the C ABI uses them
+    pub fn default_params(&self) -> Result<Params, SolveError> {
+        Params::from_sources(&self.name(), self.defaults, None, Vec::new())
+            .map_err(|err| SolveError::Input(err.to_string()))
+    }
+
+    /// Computes just `part`, turning a panic into `SolveError::Panic`
+    pub fn run(&self, part: u32, input: &[u8], params: &Params) -> Result<String, SolveError> {
+        if !(1..=self.parts).contains(&part) {
+            return Err(SolveError::UnknownPart);
+        }
+        panic::catch_unwind(AssertUnwindSafe(|| (self.solve)(part, input, params)))
+            .map_err(|_| SolveError::Panic)?
+            .map_err(SolveError::Input)
+    }
+}
+
+/// Every solved day of one year, in order
+pub struct Year {
+    pub year: u32,
+    pub days: &'static [Solution],
+}
+
+impl Year {
+    pub fn day(&self, day: u32) -> Option<&Solution> {
+        self.days.iter().find(|solution| solution.day == day)
+    }
+
+    /// `part` of `day` for `input` with the day's default params
+    pub fn solve(&self, day: u32, part: u32, input: &[u8]) -> Result<String, SolveError> {
+        let solution = self.day(day).ok_or(SolveError::UnknownDay)?;
+        solution.run(part, input, &solution.default_params()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minimize::Lines;
+
+    const DAYS: &[Solution] = &[Solution {
+        day: 3,
+        parts: 1,
+        dir: ".",
+        version: "1",
+        defaults: &[("scale", "2")],
+        units: &[&Lines],
+        validate: |_| Ok(()),
+        approach: "Length times scale",
+        svg: None,
+        generate: None,
+        solve: |_, input, params| {
+            let scale: usize = params.get("scale")?;
+            match input {
+                b"boom" => panic!("boom"),
+                b"" => Err("empty input".to_string()),
+                _ => text(Ok(input.len() * scale)),
+            }
+        },
+    }];
+    const YEAR: Year = Year {
+        year: 2000,
+        days: DAYS,
+    };
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(YEAR.solve(3, 1, b"abc"), Ok("6".to_string()));
+        assert_eq!(YEAR.solve(3, 2, b"abc"), Err(SolveError::UnknownPart));
+        assert_eq!(YEAR.solve(4, 1, b"abc"), Err(SolveError::UnknownDay));
+        assert_eq!(
+            YEAR.solve(3, 1, b""),
+            Err(SolveError::Input("empty input".to_string()))
+        );
+        assert_eq!(YEAR.solve(3, 1, b"boom"), Err(SolveError::Panic));
+        assert_eq!(YEAR.days[0].name(), "day03");
+    }
+}
@@ -0,0 +1,144 @@
+// SVG drawings of puzzle inputs, for the runner's dashboard
+//
+// Just enough SVG for grids and polygons. Callers draw in puzzle units (a
+// grid cell is 1 by 1, a day09 tile is at its own coordinates) and `finish`
+// sets the viewBox to those units, scaled so the longer side is `SIZE`
+// pixels.
+
+use std::fmt::Write;
+
+/// Pixels along the longer side of a finished drawing
+const SIZE: f64 = 640.0;
+
+pub struct Svg {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    body: String,
+}
+
+/// `text` with the characters XML reserves escaped
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+impl Svg {
+    /// A drawing of the `width` by `height` area from the origin
+    pub fn new(width: f64, height: f64) -> Self {
+        Self::with_origin(0.0, 0.0, width, height)
+    }
+
+    /// A drawing of the `width` by `height` area from (`x`, `y`)
+    pub fn with_origin(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            x,
+            y,
+            width: width.max(1.0),
+            height: height.max(1.0),
+            body: String::new(),
+        }
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: &str) -> &mut Self {
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="{fill}"/>"#
+        );
+        self
+    }
+
+    /// A rectangle with a tooltip
+    pub fn rect_titled(
+        &mut self,
+        (x, y, width, height): (f64, f64, f64, f64),
+        fill: &str,
+        title: &str,
+    ) -> &mut Self {
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="{fill}"><title>{}</title></rect>"#,
+            escape(title)
+        );
+        self
+    }
+
+    pub fn circle(&mut self, x: f64, y: f64, r: f64, fill: &str) -> &mut Self {
+        let _ = writeln!(
+            self.body,
+            r#"<circle cx="{x}" cy="{y}" r="{r}" fill="{fill}"/>"#
+        );
+        self
+    }
+
+    /// A closed outline through `points`; `fill` may be `none`
+    pub fn polygon(
+        &mut self,
+        points: &[(f64, f64)],
+        fill: &str,
+        stroke: &str,
+        stroke_width: f64,
+    ) -> &mut Self {
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+        let _ = writeln!(
+            self.body,
+            r#"<polygon points="{}" fill="{fill}" stroke="{stroke}" stroke-width="{stroke_width}"/>"#,
+            points.join(" ")
+        );
+        self
+    }
+
+    /// The drawing's title, shown by browsers as a tooltip
+    pub fn title(&mut self, title: &str) -> &mut Self {
+        let _ = writeln!(self.body, "<title>{}</title>", escape(title));
+        self
+    }
+
+    pub fn finish(&self) -> String {
+        let scale = SIZE / self.width.max(self.height);
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" \
+             width=\"{:.0}\" height=\"{:.0}\">\n{}</svg>\n",
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            self.width * scale,
+            self.height * scale,
+            self.body
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg() {
+        let mut svg = Svg::new(4.0, 2.0);
+        svg.title("a <b> & \"c\"")
+            .rect(0.0, 0.0, 1.0, 1.0, "red")
+            .circle(2.5, 1.0, 0.5, "blue")
+            .polygon(&[(0.0, 0.0), (4.0, 0.0), (4.0, 2.0)], "none", "black", 0.1);
+        assert_eq!(
+            svg.finish(),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 4 2\" width=\"640\" height=\"320\">\n\
+             <title>a &lt;b&gt; &amp; &quot;c&quot;</title>\n\
+             <rect x=\"0\" y=\"0\" width=\"1\" height=\"1\" fill=\"red\"/>\n\
+             <circle cx=\"2.5\" cy=\"1\" r=\"0.5\" fill=\"blue\"/>\n\
+             <polygon points=\"0,0 4,0 4,2\" fill=\"none\" stroke=\"black\" stroke-width=\"0.1\"/>\n\
+             </svg>\n"
+        );
+    }
+}
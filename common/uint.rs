@@ -0,0 +1,343 @@
+// Fixed-width unsigned integers of any number of 64-bit limbs
+//
+// For IDs and sums too long for u128: `U256` holds any 77-digit number.
+// Only the arithmetic the puzzles need, checked like the primitive types:
+// the operators panic on overflow and the `checked_` methods return `None`.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+use std::str::FromStr;
+
+use crate::scan::{self, Int};
+
+/// The largest power of ten in a limb, and its number of zeros
+const CHUNK: u64 = 10_000_000_000_000_000_000;
+const CHUNK_DIGITS: u32 = 19;
+
+/// An unsigned integer of `LIMBS` 64-bit limbs, least significant first
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Uint<const LIMBS: usize>([u64; LIMBS]);
+
+pub type U256 = Uint<4>;
+
+impl<const LIMBS: usize> Uint<LIMBS> {
+    pub const BITS: u32 = 64 * LIMBS as u32;
+    pub const ZERO: Self = Self([0; LIMBS]);
+    pub const MAX: Self = Self([u64::MAX; LIMBS]);
+
+    fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let mut sum = Self::ZERO;
+        let mut carry = false;
+        for (limb, (a, b)) in sum.0.iter_mut().zip(self.0.iter().zip(rhs.0)) {
+            let (partial, high) = a.overflowing_add(b);
+            let (partial, higher) = partial.overflowing_add(carry as u64);
+            *limb = partial;
+            carry = high || higher;
+        }
+        (sum, carry)
+    }
+
+    fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let mut difference = Self::ZERO;
+        let mut borrow = false;
+        for (limb, (a, b)) in difference.0.iter_mut().zip(self.0.iter().zip(rhs.0)) {
+            let (partial, low) = a.overflowing_sub(b);
+            let (partial, lower) = partial.overflowing_sub(borrow as u64);
+            *limb = partial;
+            borrow = low || lower;
+        }
+        (difference, borrow)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (sum, false) => Some(sum),
+            (_, true) => None,
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (difference, false) => Some(difference),
+            (_, true) => None,
+        }
+    }
+
+    /// Schoolbook multiplication, giving up on the first limb past the top
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let mut product = Self::ZERO;
+        for (i, &a) in self.0.iter().enumerate().filter(|&(_, &a)| a != 0) {
+            let mut carry = 0u128;
+            for (j, &b) in rhs.0.iter().enumerate() {
+                // At most (2^64 - 1)^2 + 2 (2^64 - 1), which is u128::MAX
+                let term = a as u128 * b as u128 + carry;
+                match product.0.get_mut(i + j) {
+                    Some(limb) => {
+                        let term = term + *limb as u128;
+                        *limb = term as u64;
+                        carry = term >> 64;
+                    }
+                    None if term != 0 => return None,
+                    None => {}
+                }
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(product)
+    }
+
+    pub fn checked_pow(self, exp: u32) -> Option<Self> {
+        (0..exp).try_fold(Self::from(1u8), |power, _| power.checked_mul(self))
+    }
+
+    /// The number of decimal digits less one; `None` for zero
+    pub fn checked_ilog10(self) -> Option<u32> {
+        let mut log = 0;
+        let mut rest = self;
+        while rest.bit_len() > 64 {
+            rest = rest.div_rem_limb(CHUNK).0;
+            log += CHUNK_DIGITS;
+        }
+        Some(log + rest.0[0].checked_ilog10()?)
+    }
+
+    fn bit(&self, bit: u32) -> bool {
+        self.0[(bit / 64) as usize] >> (bit % 64) & 1 == 1
+    }
+
+    /// The number of bits after the leading zeros
+    fn bit_len(&self) -> u32 {
+        match self.0.iter().rposition(|&limb| limb != 0) {
+            Some(top) => 64 * top as u32 + 64 - self.0[top].leading_zeros(),
+            None => 0,
+        }
+    }
+
+    /// Shift-and-subtract long division, one bit at a time, unless `rhs`
+    /// fits in a limb
+    fn div_rem(self, rhs: Self) -> (Self, Self) {
+        assert!(rhs != Self::ZERO, "attempt to divide by zero");
+        if rhs.bit_len() <= 64 {
+            let (quotient, remainder) = self.div_rem_limb(rhs.0[0]);
+            return (quotient, Self::from(remainder));
+        }
+        let (mut quotient, mut remainder) = (Self::ZERO, Self::ZERO);
+        for bit in (0..self.bit_len()).rev() {
+            // The remainder is below `rhs`, so doubling it overflows only
+            // when the doubled value is certainly at least `rhs`
+            let (doubled, carry) = remainder.overflowing_add(remainder);
+            remainder = doubled;
+            remainder.0[0] |= self.bit(bit) as u64;
+            if carry || remainder >= rhs {
+                remainder = remainder.overflowing_sub(rhs).0;
+                quotient.0[(bit / 64) as usize] |= 1 << (bit % 64);
+            }
+        }
+        (quotient, remainder)
+    }
+
+    /// Division by a single limb
+    fn div_rem_limb(self, rhs: u64) -> (Self, u64) {
+        let mut quotient = Self::ZERO;
+        let mut remainder = 0u128;
+        for (limb, &digit) in quotient.0.iter_mut().zip(&self.0).rev() {
+            let current = remainder << 64 | digit as u128;
+            *limb = (current / rhs as u128) as u64;
+            remainder = current % rhs as u128;
+        }
+        (quotient, remainder as u64)
+    }
+}
+
+macro_rules! impl_from {
+    ($($t:ty),*) => {$(
+        impl<const LIMBS: usize> From<$t> for Uint<LIMBS> {
+            fn from(value: $t) -> Self {
+                const { assert!(LIMBS >= 1, "a Uint needs a limb") };
+                let mut limbs = [0; LIMBS];
+                limbs[0] = value as u64;
+                Self(limbs)
+            }
+        }
+    )*};
+}
+
+impl_from!(u8, u16, u32, u64, usize);
+
+impl<const LIMBS: usize> From<u128> for Uint<LIMBS> {
+    fn from(value: u128) -> Self {
+        const { assert!(LIMBS >= 2, "a u128 needs two limbs") };
+        let mut limbs = [0; LIMBS];
+        limbs[0] = value as u64;
+        limbs[1] = (value >> 64) as u64;
+        Self(limbs)
+    }
+}
+
+impl<const LIMBS: usize> Ord for Uint<LIMBS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl<const LIMBS: usize> PartialOrd for Uint<LIMBS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const LIMBS: usize> Add for Uint<LIMBS> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).expect("attempt to add with overflow")
+    }
+}
+
+impl<const LIMBS: usize> Sub for Uint<LIMBS> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.checked_sub(rhs)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl<const LIMBS: usize> Mul for Uint<LIMBS> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs)
+            .expect("attempt to multiply with overflow")
+    }
+}
+
+impl<const LIMBS: usize> Div for Uint<LIMBS> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        self.div_rem(rhs).0
+    }
+}
+
+impl<const LIMBS: usize> Rem for Uint<LIMBS> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self {
+        self.div_rem(rhs).1
+    }
+}
+
+impl<const LIMBS: usize> Int for Uint<LIMBS> {
+    const SIGNED: bool = false;
+    const ZERO: Self = Self::ZERO;
+
+    fn push_digit(self, digit: u8, _negative: bool) -> Option<Self> {
+        self.checked_mul(Self::from(10u8))?
+            .checked_add(Self::from(digit))
+    }
+}
+
+impl<const LIMBS: usize> FromStr for Uint<LIMBS> {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        scan::parse(text.as_bytes()).ok_or_else(|| format!("invalid u{}: {text:?}", Self::BITS))
+    }
+}
+
+impl<const LIMBS: usize> fmt::Display for Uint<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // A chunk of digits at a time, lowest first
+        let mut chunks = Vec::new();
+        let mut rest = *self;
+        loop {
+            let (quotient, chunk) = rest.div_rem_limb(CHUNK);
+            chunks.push(chunk);
+            rest = quotient;
+            if rest == Self::ZERO {
+                break;
+            }
+        }
+
+        let mut digits = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            digits += &format!("{chunk:0width$}", width = CHUNK_DIGITS as usize);
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+impl<const LIMBS: usize> fmt::Debug for Uint<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every operation on two-limb values, against u128
+    #[test]
+    fn test_matches_u128() {
+        let values = [
+            0,
+            1,
+            9,
+            10,
+            u64::MAX as u128,
+            u64::MAX as u128 + 1,
+            123_456_789_012_345_678_901_234_567_890,
+            u128::MAX / 3,
+            u128::MAX - 1,
+            u128::MAX,
+        ];
+        let wide = Uint::<2>::from;
+        for a in values {
+            assert_eq!(wide(a).to_string(), a.to_string());
+            assert_eq!(a.to_string().parse(), Ok(wide(a)));
+            assert_eq!(wide(a).checked_ilog10(), a.checked_ilog10());
+            for b in values {
+                assert_eq!(wide(a).cmp(&wide(b)), a.cmp(&b));
+                assert_eq!(wide(a).checked_add(wide(b)), a.checked_add(b).map(wide));
+                assert_eq!(wide(a).checked_sub(wide(b)), a.checked_sub(b).map(wide));
+                assert_eq!(wide(a).checked_mul(wide(b)), a.checked_mul(b).map(wide));
+                if b != 0 {
+                    assert_eq!(wide(a) / wide(b), wide(a / b), "{a} / {b}");
+                    assert_eq!(wide(a) % wide(b), wide(a % b), "{a} % {b}");
+                }
+            }
+        }
+        assert_eq!(wide(10).checked_pow(38), 10u128.checked_pow(38).map(wide));
+        assert_eq!(wide(10).checked_pow(39), None);
+    }
+
+    #[test]
+    fn test_wide() {
+        let digits = "9".repeat(77);
+        let max: U256 = digits.parse().unwrap();
+        assert_eq!(max.to_string(), digits);
+        assert_eq!(max.checked_ilog10(), Some(76));
+        assert_eq!(format!("{:>80}", max), format!("{digits:>80}"));
+        assert!(U256::MAX > max);
+        assert_eq!(U256::MAX.to_string().len(), 78);
+        assert_eq!(
+            format!("{digits}9").parse::<U256>(),
+            Err(format!("invalid u256: \"{digits}9\""))
+        );
+
+        let square = U256::from(u128::MAX) * U256::from(u128::MAX);
+        assert_eq!(square / U256::from(u128::MAX), U256::from(u128::MAX));
+        assert_eq!(square % U256::from(u128::MAX), U256::ZERO);
+        assert_eq!(U256::MAX / U256::MAX, U256::from(1u8));
+        assert_eq!(U256::MAX % (U256::MAX - U256::from(1u8)), U256::from(1u8));
+        assert_eq!(U256::MAX.checked_add(U256::from(1u8)), None);
+        assert_eq!(U256::MAX.checked_mul(U256::from(2u8)), None);
+        assert_eq!(U256::ZERO.checked_ilog10(), None);
+        assert_eq!(U256::ZERO.to_string(), "0");
+    }
+}
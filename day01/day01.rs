@@ -1,50 +1,491 @@
-use std::fs;
+// Advent of Code 2025 - Day 01
+// Count how often a safe's dials land on or click past their marks
 
-fn parse_line(line: &str) -> i32 {
-    let direction = line.chars().next().unwrap();
-    let value: i32 = line[1..].parse().unwrap();
-    if direction == 'L' {
-        -value
-    } else {
-        value
+pub mod dial;
+pub mod lang;
+pub mod num;
+pub mod occupancy;
+pub mod odometer;
+pub mod setup;
+pub mod trace;
+pub mod what_if;
+
+use common::minimize::{Lines, Units};
+use common::params::Params;
+use common::scan;
+use dial::{Dial, Events, Policy};
+use lang::{Action, Program};
+pub use num::DialInt;
+use odometer::Odometer;
+pub use setup::Setup;
+
+pub const SOLVER_VERSION: &str = "1";
+
+pub const DEFAULTS: &[(&str, &str)] = &[
+    ("start", "50"),
+    ("size", "100"),
+    ("marks", "0"),
+    ("bits", "64"),
+];
+
+/// A single step of a program, with where it came from for traces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction<'a, T> {
+    line: usize,
+    text: &'a [u8],
+    /// 1-based dial number, or `None` for the lowest dial
+    dial: Option<usize>,
+    action: Action<T>,
+}
+
+impl<T> Instruction<'_, T> {
+    fn dial_index(&self) -> usize {
+        self.dial.map_or(0, |dial| dial - 1)
     }
 }
 
-fn count_crossings(dial: i32, movement: i32) -> i32 {
-    let full_rotations = movement.abs() / 100;
-    let sign = if movement >= 0 { 1 } else { -1 };
-    let remainder = movement - sign * 100 * full_rotations;
-    let new_pos = dial + remainder;
+pub fn parse_input<T: DialInt>(input: &[u8]) -> Result<Program<'_, T>, String> {
+    lang::parse(input).map_err(|err| err.render(input))
+}
+
+/// Clicks of a `movement` from `dial` (in `0..size`) that leave it on 0.
+/// Constant time for any distance; no intermediate exceeds `|movement|`
+/// or `size`, so only `movement == T::MIN` with `size == 1` overflows.
+fn count_crossings<T: DialInt>(dial: T, movement: T, size: T) -> T {
+    let full_rotations = movement / size;
+    let remainder = movement % size;
+
+    let boundary_crossing = (remainder < T::ZERO && dial > T::ZERO && dial <= -remainder)
+        || (remainder > T::ZERO && remainder >= size - dial);
 
-    let boundary_crossing = if remainder < 0 && dial > 0 && new_pos <= 0 {
-        1
-    } else if remainder > 0 && new_pos > 99 {
-        1
+    let full_rotations = if full_rotations < T::ZERO {
+        -full_rotations
     } else {
-        0
+        full_rotations
     };
+    if boundary_crossing {
+        full_rotations + T::ONE
+    } else {
+        full_rotations
+    }
+}
+
+fn normalize_dial<T: DialInt>(dial: T, size: T) -> T {
+    dial.rem_euclid(size)
+}
 
-    full_rotations + boundary_crossing
+/// Events counted under `policy` for each dial of `setup`, lowest first
+pub fn tally<T: DialInt>(
+    setup: &Setup<T>,
+    program: &Program<T>,
+    policy: fn(Vec<T>) -> Policy<T>,
+) -> Result<Vec<T>, String> {
+    setup.check(program)?;
+
+    let mut odometer = setup.odometer(policy)?;
+    for instruction in program.instructions() {
+        odometer.apply(instruction.dial_index(), instruction.action);
+    }
+
+    odometer
+        .dials()
+        .iter()
+        .map(Dial::count)
+        .collect::<Option<_>>()
+        .ok_or_else(|| format!("count overflows {}", T::NAME))
 }
 
-fn normalize_dial(dial: i32) -> i32 {
-    ((dial % 100) + 100) % 100
+/// Sum of the per-dial tallies, widened for printing
+pub fn total<T: DialInt>(tally: &[T]) -> Result<i128, String> {
+    tally
+        .iter()
+        .try_fold(T::ZERO, |total, &count| total.checked_add(count))
+        .map(Into::into)
+        .ok_or_else(|| format!("count overflows {}", T::NAME))
 }
 
-fn main() {
-    let content = fs::read_to_string("input.txt").expect("Failed to read input.txt");
+/// Landings on the marks (part 1: `true`) or clicks through them (part 2:
+/// `false`) for each of `landings`, summed over the dials, with the
+/// arithmetic done in the width given by the `bits` param. The input is
+/// parsed once however many parts are asked for.
+fn counts(input: &[u8], params: &Params, landings: &[bool]) -> Result<Vec<i128>, String> {
+    fn counts_in<T: DialInt>(
+        input: &[u8],
+        params: &Params,
+        landings: &[bool],
+    ) -> Result<Vec<i128>, String> {
+        let setup = Setup::from_params(params)?;
+        let program = parse_input(input)?;
+        landings
+            .iter()
+            .map(|&landings| {
+                let policy = if landings {
+                    Policy::LandingsOn
+                } else {
+                    Policy::PassesThrough
+                };
+                total(&tally::<T>(&setup, &program, policy)?)
+            })
+            .collect()
+    }
+
+    match params.get::<u32>("bits")? {
+        32 => counts_in::<i32>(input, params, landings),
+        64 => counts_in::<i64>(input, params, landings),
+        128 => counts_in::<i128>(input, params, landings),
+        bits => Err(format!("bits must be 32, 64 or 128, got {bits}")),
+    }
+}
+
+pub fn part1(input: &[u8], params: &Params) -> Result<i128, String> {
+    Ok(counts(input, params, &[true])?[0])
+}
+
+pub fn part2(input: &[u8], params: &Params) -> Result<i128, String> {
+    Ok(counts(input, params, &[false])?[0])
+}
+
+/// Both answers for `input`, summed over the dials (just one by default)
+pub fn run(input: &[u8], params: &Params) -> Result<(i128, i128), String> {
+    match counts(input, params, &[true, false])?[..] {
+        [landings, passes] => Ok((landings, passes)),
+        _ => unreachable!("one count per part"),
+    }
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_input::<i128>(input).map(drop)
+}
+
+/// This day's minimizer passes: one line at a time
+pub const UNITS: &[&dyn Units] = &[&Lines];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+
+    const EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    fn params(args: &[&str]) -> Params {
+        let args = args.iter().map(|arg| arg.to_string());
+        Params::from_sources("day01", DEFAULTS, None, args).unwrap()
+    }
+
+    type Actions<T> = Vec<(Option<usize>, Action<T>)>;
+
+    fn actions<T: DialInt>(input: &[u8]) -> Result<Actions<T>, String> {
+        parse_input(input).map(|program| {
+            program
+                .instructions()
+                .map(|instruction| (instruction.dial, instruction.action))
+                .collect()
+        })
+    }
 
-    let (_, zeros, crossings) = content.lines().filter(|line| !line.is_empty()).fold(
-        (50, 0, 0),
-        |(dial, zeros, crossings), line| {
-            let movement = parse_line(line);
-            let new_dial = normalize_dial(dial + movement);
-            let new_zeros = if new_dial == 0 { zeros + 1 } else { zeros };
-            let new_crossings = crossings + count_crossings(dial, movement);
-            (new_dial, new_zeros, new_crossings)
-        },
-    );
+    #[test]
+    fn test_example() {
+        assert_eq!(run(EXAMPLE.as_bytes(), &params(&[])), Ok((3, 6)));
 
-    println!("{}", zeros);
-    println!("{}", crossings);
+        let err = Setup::<i64>::from_params(&params(&["--param=size=100,0"]))
+            .and_then(|setup| setup.odometer(Policy::LandingsOn));
+        assert_eq!(err.err().unwrap(), "dial size must be positive, got 0");
+        assert_eq!(
+            run(EXAMPLE.as_bytes(), &params(&["--param=size=-5"])),
+            Err("dial size must be positive, got -5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let program = parse_input(b"L68\n\nR48\n").unwrap();
+        let dial = Dial::new(100, 50, Policy::PassesThrough(vec![0])).unwrap();
+        let odometer = Odometer::new(vec![dial]);
+        let steps: Vec<_> = trace::Trace::new(odometer.clone(), program.instructions()).collect();
+        assert_eq!(
+            steps
+                .iter()
+                .map(|s| (s.line, s.from, s.to, s.landed, s.crossings))
+                .collect::<Vec<_>>(),
+            vec![(1, 50, 82, false, 1), (3, 82, 30, false, 1)]
+        );
+
+        let mut csv = Vec::new();
+        let steps = trace::Trace::new(odometer.clone(), program.instructions());
+        trace::export(steps, trace::Format::Csv, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "line,instruction,dial,from,to,landed,crossings\n1,L68,1,50,82,false,1\n3,R48,1,82,30,false,1\n"
+        );
+
+        let mut json = Vec::new();
+        let steps = trace::Trace::new(odometer, program.instructions());
+        trace::export(steps, trace::Format::Json, &mut json).unwrap();
+        assert!(String::from_utf8(json).unwrap().starts_with(
+            "[\n  {\"line\": 1, \"instruction\": \"L68\", \"dial\": 1, \"from\": 50, \"to\": 82, \"landed\": false, \"crossings\": 1},\n"
+        ));
+    }
+
+    #[test]
+    fn test_marked_positions() {
+        let mut dial = Dial::new(10, 0, Policy::PassesThrough(vec![3, 13, 7])).unwrap();
+        let events = dial.apply(-25);
+        assert_eq!((events.from, events.to, events.landed), (0, 5, false));
+        // 7, 3, 7, 3, 7 going down from 0 to 5
+        assert_eq!(events.passes, 5);
+        assert_eq!(dial.apply(2).passes, 1);
+
+        let landings = params(&["--param=marks=32,50", "--param=start=0"]);
+        assert_eq!(run(EXAMPLE.as_bytes(), &landings).map(|(l, _)| l), Ok(4));
+    }
+
+    #[test]
+    fn test_odometer() {
+        let two_dials = params(&["--param=size=10,10", "--param=start=0"]);
+        // R25 carries 2 into dial 2, 2:R3 moves it directly, L1 borrows one back
+        let input = b"R25\n2:R3\nL5\nL1\n";
+        let setup = Setup::<i64>::from_params(&two_dials).unwrap();
+        let program = parse_input(input).unwrap();
+        assert_eq!(tally(&setup, &program, Policy::LandingsOn), Ok(vec![1, 0]));
+        assert_eq!(
+            tally(&setup, &program, Policy::PassesThrough),
+            Ok(vec![3, 0])
+        );
+        assert_eq!(run(input, &two_dials), Ok((1, 3)));
+
+        let mut odometer = Setup::<i64>::from_params(&two_dials)
+            .and_then(|setup| setup.odometer(Policy::LandingsOn))
+            .unwrap();
+        let moved: Vec<_> = odometer
+            .apply(0, Action::Turn(-1))
+            .into_iter()
+            .map(|(idx, events)| (idx, events.to))
+            .collect();
+        assert_eq!(moved, vec![(0, 9), (1, 9)]);
+
+        assert!(run(b"R1\n3:L2\n", &two_dials).is_err());
+        assert!(run(b"0:L2\n", &two_dials).is_err());
+    }
+
+    #[test]
+    fn test_what_if() {
+        // Replays `actions`, with one step removed (false) or flipped (true)
+        fn landings(actions: &[Action<i64>], edit: Option<(usize, bool)>) -> i64 {
+            let mut dial = Dial::new(100, 50, Policy::LandingsOn(vec![0, 30])).unwrap();
+            for (idx, &action) in actions.iter().enumerate() {
+                match (action, edit) {
+                    (_, Some((at, false))) if at == idx => {}
+                    (Action::Turn(movement), Some((at, true))) if at == idx => {
+                        dial.apply(-movement);
+                    }
+                    (Action::Turn(movement), _) => {
+                        dial.apply(movement);
+                    }
+                    (Action::Set(position), _) => {
+                        dial.set(position);
+                    }
+                }
+            }
+            dial.count().unwrap()
+        }
+
+        let source = format!("{EXAMPLE} =30 L30 R130 =0 2*(R70 L40)");
+        let program = parse_input::<i64>(source.as_bytes()).unwrap();
+        let actions: Vec<_> = program.instructions().map(|i| i.action).collect();
+        let dial = Dial::new(100, 50, Policy::LandingsOn(vec![0, 30])).unwrap();
+        let influences = what_if::analyze(dial, program.instructions());
+
+        let base = landings(&actions, None);
+        for (idx, influence) in influences.iter().enumerate() {
+            assert_eq!(influence.step, idx + 1);
+            let removed = landings(&actions, Some((idx, false))) - base;
+            assert_eq!(influence.removed, removed, "removing step {}", idx + 1);
+            if let Action::Turn(_) = actions[idx] {
+                let flipped = landings(&actions, Some((idx, true))) - base;
+                assert_eq!(
+                    influence.flipped,
+                    Some(flipped),
+                    "flipping step {}",
+                    idx + 1
+                );
+            }
+        }
+        // L68 from 50 sweeps past 30 and 0
+        assert_eq!(influences[0].crossings, 2);
+
+        let top = what_if::top(&influences, 3);
+        assert_eq!(top.len(), 3);
+        assert!(top
+            .windows(2)
+            .all(|pair| { pair[0].largest_change() >= pair[1].largest_change() }));
+
+        let mut table = Vec::new();
+        what_if::render(&influences[..1], &mut table).unwrap();
+        assert_eq!(
+            String::from_utf8(table).unwrap(),
+            "    step   line  instruction       crossings  flipped  removed\n       1      1  L68                       2       -3       -2\n"
+        );
+
+        // Dial-addressed steps are rejected, as they are without --what-if
+        let setup = Setup::<i64>::from_params(&params(&[])).unwrap();
+        let program = parse_input(b"R50\n2:R50").unwrap();
+        let err = setup.single_dial(&program, "what-if analysis").unwrap_err();
+        assert_eq!(err, "line 2: there are only 1 dials");
+        let setup = Setup::<i64>::from_params(&params(&["--param=size=100,10"])).unwrap();
+        let err = setup.single_dial(&program, "what-if analysis").unwrap_err();
+        assert_eq!(err, "what-if analysis needs a single dial");
+    }
+
+    #[test]
+    fn test_histogram() {
+        let setup = Setup::<i64>::from_params(&params(&[])).unwrap();
+        let program = parse_input(EXAMPLE.as_bytes()).unwrap();
+        let dial = setup.single_dial(&program, "a histogram").unwrap();
+        let histogram = occupancy::histogram(dial, program.instructions()).unwrap();
+        assert_eq!((histogram.landings[0], histogram.passes[0]), (3, 6));
+        assert_eq!(histogram.landings.iter().sum::<u64>(), 10);
+        // Every click lands somewhere
+        assert_eq!(histogram.passes.iter().sum::<u128>(), 462);
+
+        // Click by click on a small dial, wrapping both ways and jumping
+        let program = parse_input::<i64>(b"R23 L9 =3 L17 R6 L0 =6").unwrap();
+        let mut expected = (vec![0; 7], vec![0; 7]);
+        let mut position = 4;
+        for instruction in program.instructions() {
+            match instruction.action {
+                Action::Turn(movement) => {
+                    for _ in 0..movement.abs() {
+                        position = (position + movement.signum()).rem_euclid(7);
+                        expected.1[position as usize] += 1;
+                    }
+                }
+                Action::Set(to) => position = to,
+            }
+            expected.0[position as usize] += 1;
+        }
+        let dial = Dial::new(7, 4, Policy::LandingsOn(vec![0])).unwrap();
+        let histogram = occupancy::histogram(dial, program.instructions()).unwrap();
+        assert_eq!((histogram.landings, histogram.passes), expected);
+
+        let mut bars = Vec::new();
+        let histogram = occupancy::Histogram {
+            landings: vec![2, 0],
+            passes: vec![4, 1],
+        };
+        occupancy::render(&histogram, occupancy::Style::Bars, &mut bars).unwrap();
+        let bars = String::from_utf8(bars).unwrap();
+        let full = "#".repeat(40);
+        assert_eq!(
+            bars.lines().collect::<Vec<_>>(),
+            vec![
+                format!("0 | {full}        2 | {full} 4"),
+                format!("1 | {:40}        0 | {} 1", "", "#".repeat(10)),
+            ]
+        );
+
+        // Dial-addressed steps are rejected, as they are without --histogram
+        let program = parse_input(b"R50\n2:R50").unwrap();
+        let err = setup.single_dial(&program, "a histogram").unwrap_err();
+        assert_eq!(err, "line 2: there are only 1 dials");
+    }
+
+    #[test]
+    fn test_language() {
+        use Action::{Set, Turn};
+
+        let source = b"# warm up\nR10 L5  2:=42 # two on one line\n2*(R1 3*(L2) 0*(R9))\n";
+        let mut expected = vec![(None, Turn(10)), (None, Turn(-5)), (Some(2), Set(42))];
+        for _ in 0..2 {
+            expected.push((None, Turn(1)));
+            expected.extend([(None, Turn(-2)); 3]);
+        }
+        assert_eq!(actions::<i64>(source), Ok(expected));
+
+        let one_line = EXAMPLE.replace('\n', " ");
+        assert_eq!(run(one_line.as_bytes(), &params(&[])), Ok((3, 6)));
+        // Jumps land but make no clicks
+        assert_eq!(run(b"=0 =100 R100", &params(&[])), Ok((3, 1)));
+
+        // Far more steps than would fit in memory
+        let program = parse_input::<i64>(b"1000000000000000000*(R1 L1)").unwrap();
+        let mut instructions = program.instructions().skip(1_000_001);
+        assert_eq!(instructions.next().map(|i| i.action), Some(Turn(-1)));
+
+        let error = |source: &[u8]| lang::parse::<i64>(source).unwrap_err();
+        assert_eq!(
+            error(b"R10\nL5 Rx").render(b"R10\nL5 Rx"),
+            "line 2, column 5: unexpected character \"x\"\n  L5 Rx\n      ^"
+        );
+        assert_eq!(error(b"R10 L").message, "expected a number");
+        assert_eq!(error(b"R10 L").span, 5..5);
+        assert_eq!(error("R10 \u{e9}".as_bytes()).span, 4..6);
+        assert_eq!(error(b"3*(R1").span, 2..3);
+        assert_eq!(error(b"R1 )").span, 3..4);
+        assert_eq!(error(b"R1 5").span, 3..4);
+        assert_eq!(actions::<i64>(b"  \n\t\n"), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_extreme_rotations() {
+        let turn = |input| actions::<i128>(input).map(|actions| actions[0].1);
+        assert_eq!(
+            actions(b"R9223372036854775807"),
+            Ok(vec![(None, Action::Turn(i64::MAX))])
+        );
+        assert!(actions::<i64>(b"L9223372036854775808").is_err());
+        assert_eq!(
+            turn(b"L9223372036854775808"),
+            Ok(Action::Turn(i64::MIN as i128))
+        );
+        assert!(actions::<i32>(b"R3000000000").is_err());
+        assert!(actions::<i64>(b"R-5").is_err());
+
+        // Against plain i128 arithmetic, which cannot overflow for i64 inputs
+        let max = i64::MAX;
+        for size in [1, 2, 100, max - 1, max] {
+            for from in [0, 1.min(size - 1), size / 2, size - 1] {
+                for movement in [0, 1, -1, size, -size, max - 1, max, -max] {
+                    let mut dial = Dial::new(size, from, Policy::PassesThrough(vec![0])).unwrap();
+                    let events = dial.apply(movement);
+
+                    let (f, m, s) = (from as i128, movement as i128, size as i128);
+                    let passes = if m >= 0 {
+                        (f + m).div_euclid(s)
+                    } else {
+                        (f - 1).div_euclid(s) - (f + m - 1).div_euclid(s)
+                    };
+                    let expected = ((f + m).rem_euclid(s), (f + m).div_euclid(s), passes);
+                    let got = (
+                        events.to as i128,
+                        events.carry as i128,
+                        events.passes as i128,
+                    );
+                    assert_eq!(
+                        got, expected,
+                        "size {size}, from {from}, movement {movement}"
+                    );
+                }
+            }
+        }
+
+        let mut dial = Dial::new(i128::MAX, 5, Policy::PassesThrough(vec![0])).unwrap();
+        let events = dial.apply(i128::MAX);
+        assert_eq!((events.to, events.passes, events.carry), (5, 1, 1));
+
+        let clicks = b"R2147483647\nR2147483647\n";
+        let size_one = |bits: &str| params(&["--param=size=1", &format!("--param=bits={bits}")]);
+        assert!(run(clicks, &size_one("32")).is_err());
+        assert_eq!(run(clicks, &size_one("64")), Ok((2, 4294967294)));
+    }
+
+    #[test]
+    fn fuzz_parse_input() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(|input| {
+                parse_input::<i64>(input).map(|program| program.instructions().take(10_000).count())
+            });
+    }
 }
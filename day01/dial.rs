@@ -0,0 +1,135 @@
+// A single dial and the clicks it counts
+
+use super::*;
+
+/// Which clicks a dial counts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Policy<T> {
+    /// Rotations that end on one of the marked positions
+    LandingsOn(Vec<T>),
+    /// Every click that leaves the dial on a marked position, the final
+    /// one included
+    PassesThrough(Vec<T>),
+}
+
+/// What a single rotation did, for all marks of the dial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Events<T> {
+    pub from: T,
+    pub to: T,
+    pub landed: bool,
+    /// Never more than the distance turned, since marks are distinct
+    pub passes: T,
+    /// Full revolutions past the top (negative going left), which turn
+    /// the next dial of an odometer
+    pub carry: T,
+}
+
+#[derive(Debug, Clone)]
+pub struct Dial<T> {
+    size: T,
+    position: T,
+    policy: Policy<T>,
+    /// `None` once the total no longer fits in `T`
+    count: Option<T>,
+}
+
+impl<T: DialInt> Dial<T> {
+    pub fn new(size: T, start: T, policy: Policy<T>) -> Result<Self, String> {
+        if size <= T::ZERO {
+            return Err(format!("dial size must be positive, got {size}"));
+        }
+        let policy = match policy {
+            Policy::LandingsOn(marks) => Policy::LandingsOn(normalize_marks(marks, size)),
+            Policy::PassesThrough(marks) => Policy::PassesThrough(normalize_marks(marks, size)),
+        };
+        Ok(Self {
+            size,
+            position: normalize_dial(start, size),
+            policy,
+            count: Some(T::ZERO),
+        })
+    }
+
+    /// Events counted by the policy so far, or `None` on overflow
+    pub fn count(&self) -> Option<T> {
+        self.count
+    }
+
+    pub fn size(&self) -> T {
+        self.size
+    }
+
+    /// The marks in `0..size`, sorted and without duplicates
+    pub fn marks(&self) -> &[T] {
+        match &self.policy {
+            Policy::LandingsOn(marks) | Policy::PassesThrough(marks) => marks,
+        }
+    }
+
+    /// Turns the dial by `movement` clicks (negative is left), which
+    /// must not be `T::MIN`
+    pub fn apply(&mut self, movement: T) -> Events<T> {
+        let from = self.position;
+        // `from + movement` may not fit, so wrap the remainder by hand
+        let remainder = movement.rem_euclid(self.size);
+        let wraps = remainder >= self.size - from;
+        let to = if wraps {
+            remainder - (self.size - from)
+        } else {
+            from + remainder
+        };
+        let carry = movement.div_euclid(self.size) + if wraps { T::ONE } else { T::ZERO };
+
+        // Passing mark m is passing zero on a dial shifted by m
+        let landed = self.marks().contains(&to);
+        let passes = self.marks().iter().fold(T::ZERO, |passes, &mark| {
+            let shifted = normalize_dial(from - mark, self.size);
+            passes + count_crossings(shifted, movement, self.size)
+        });
+
+        self.position = to;
+        let counted = match self.policy {
+            Policy::LandingsOn(_) if landed => T::ONE,
+            Policy::LandingsOn(_) => T::ZERO,
+            Policy::PassesThrough(_) => passes,
+        };
+        self.count = self.count.and_then(|count| count.checked_add(counted));
+        Events {
+            from,
+            to,
+            landed,
+            passes,
+            carry,
+        }
+    }
+}
+
+impl<T: DialInt> Dial<T> {
+    /// Jumps straight to `position`. No clicks are made, so nothing is
+    /// passed through and nothing carries, but the jump can land on a mark.
+    pub fn set(&mut self, position: T) -> Events<T> {
+        let from = self.position;
+        let to = normalize_dial(position, self.size);
+        let landed = self.marks().contains(&to);
+
+        self.position = to;
+        if landed && matches!(self.policy, Policy::LandingsOn(_)) {
+            self.count = self.count.and_then(|count| count.checked_add(T::ONE));
+        }
+        Events {
+            from,
+            to,
+            landed,
+            passes: T::ZERO,
+            carry: T::ZERO,
+        }
+    }
+}
+
+fn normalize_marks<T: DialInt>(marks: Vec<T>, size: T) -> Vec<T> {
+    let mut marks: Vec<T> = marks.into_iter().map(|m| normalize_dial(m, size)).collect();
+    marks.sort_unstable();
+    marks.dedup();
+    marks
+}
@@ -0,0 +1,380 @@
+// The dial language: tokenizer, parser and the programs it builds
+
+use super::*;
+use common::scan::Int;
+use std::ops::Range;
+
+/// Repeat blocks nest at most this deep, which bounds the parser's
+/// recursion
+const MAX_NESTING: usize = 64;
+
+/// A syntax error and the bytes of the source it is about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub span: Range<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(span: Range<usize>, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+        }
+    }
+
+    /// `line L, column C: message`, then the source line with the span
+    /// underlined
+    pub fn render(&self, source: &[u8]) -> String {
+        let line_start = source[..self.span.start]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |at| at + 1);
+        let line_end = source[line_start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(source.len(), |at| line_start + at);
+        let line = source[..line_start].iter().filter(|&&b| b == b'\n').count() + 1;
+        let column = scan::show(&source[line_start..self.span.start])
+            .chars()
+            .count();
+        let width = scan::show(&source[self.span.start..self.span.end.min(line_end)])
+            .chars()
+            .count();
+
+        format!(
+            "line {line}, column {}: {}\n  {}\n  {}{}",
+            column + 1,
+            self.message,
+            scan::show(&source[line_start..line_end]),
+            " ".repeat(column),
+            "^".repeat(width.max(1))
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Colon,
+    Star,
+    Open,
+    Close,
+    Left,
+    Right,
+    Equals,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    kind: Kind,
+    start: usize,
+    end: usize,
+    line: usize,
+}
+
+impl Token {
+    fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// Splits `source` into tokens, dropping whitespace and `#` comments
+fn tokenize(source: &[u8]) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut pos = 0;
+    while let Some(&byte) = source.get(pos) {
+        let start = pos;
+        pos += 1;
+        let kind = match byte {
+            b'\n' => {
+                line += 1;
+                continue;
+            }
+            b' ' | b'\t' | b'\r' => continue,
+            b'#' => {
+                while source.get(pos).is_some_and(|&b| b != b'\n') {
+                    pos += 1;
+                }
+                continue;
+            }
+            b'0'..=b'9' => {
+                while source.get(pos).is_some_and(u8::is_ascii_digit) {
+                    pos += 1;
+                }
+                Kind::Number
+            }
+            b':' => Kind::Colon,
+            b'*' => Kind::Star,
+            b'(' => Kind::Open,
+            b')' => Kind::Close,
+            b'L' => Kind::Left,
+            b'R' => Kind::Right,
+            b'=' => Kind::Equals,
+            _ => {
+                // Underline the whole character, not just its first byte
+                let chunk = source[start..].utf8_chunks().next().unwrap();
+                let len = chunk
+                    .valid()
+                    .chars()
+                    .next()
+                    .map_or(chunk.invalid().len(), char::len_utf8);
+                let span = start..start + len;
+                let message = format!(
+                    "unexpected character {:?}",
+                    scan::show(&source[span.clone()])
+                );
+                return Err(ParseError::new(span, message));
+            }
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            end: pos,
+            line,
+        });
+    }
+    Ok(tokens)
+}
+
+/// What an instruction does to its dial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action<T> {
+    /// Turn by this many clicks, negative to the left
+    Turn(T),
+    /// Jump straight to this position without clicking through the
+    /// ones in between
+    Set(T),
+}
+
+#[derive(Debug, Clone)]
+enum Item<T> {
+    Step {
+        dial: Option<usize>,
+        action: Action<T>,
+        span: Range<usize>,
+        line: usize,
+    },
+    /// Runs `body` `count` times; never empty and never zero times, so
+    /// every pass yields at least one step
+    Repeat { count: u64, body: Vec<Item<T>> },
+}
+
+impl<T: Copy> Item<T> {
+    fn instruction<'a>(&self, source: &'a [u8]) -> Option<Instruction<'a, T>> {
+        match self {
+            Item::Step {
+                dial,
+                action,
+                span,
+                line,
+            } => Some(Instruction {
+                line: *line,
+                text: &source[span.clone()],
+                dial: *dial,
+                action: *action,
+            }),
+            Item::Repeat { .. } => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    source: &'a [u8],
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek()?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    /// Span for "expected ..." errors: the next token, or the end of
+    /// the source
+    fn here(&self) -> Range<usize> {
+        self.peek()
+            .map_or(self.source.len()..self.source.len(), |token| token.span())
+    }
+
+    fn expect(&mut self, kind: Kind, what: &str) -> Result<Token, ParseError> {
+        match self.peek() {
+            Some(token) if token.kind == kind => {
+                self.pos += 1;
+                Ok(token)
+            }
+            _ => Err(ParseError::new(self.here(), format!("expected {what}"))),
+        }
+    }
+
+    fn number<N: Int>(&self, token: Token, what: &str) -> Result<N, ParseError> {
+        scan::parse(&self.source[token.span()])
+            .ok_or_else(|| ParseError::new(token.span(), format!("{what} is too large")))
+    }
+
+    /// Items up to the end of the source, or up to the `)` closing the
+    /// block opened by `open` (which is left for the caller)
+    fn items<T: DialInt>(
+        &mut self,
+        open: Option<Token>,
+        depth: usize,
+    ) -> Result<Vec<Item<T>>, ParseError> {
+        let mut items = Vec::new();
+        loop {
+            match (self.peek(), open) {
+                (None, None) => return Ok(items),
+                (None, Some(open)) => {
+                    return Err(ParseError::new(open.span(), "unclosed '('"));
+                }
+                (Some(token), None) if token.kind == Kind::Close => {
+                    return Err(ParseError::new(token.span(), "unmatched ')'"));
+                }
+                (Some(token), Some(_)) if token.kind == Kind::Close => return Ok(items),
+                _ => items.extend(self.item(depth)?),
+            }
+        }
+    }
+
+    /// One step or repeat block; `None` for a block that runs nothing
+    fn item<T: DialInt>(&mut self, depth: usize) -> Result<Option<Item<T>>, ParseError> {
+        let first = self.peek().unwrap();
+        if first.kind != Kind::Number {
+            return self.step(None, first).map(Some);
+        }
+
+        self.pos += 1;
+        match self.next() {
+            Some(token) if token.kind == Kind::Colon => {
+                let dial = self.number::<usize>(first, "dial number")?;
+                if dial == 0 {
+                    return Err(ParseError::new(first.span(), "dials are numbered from 1"));
+                }
+                self.step(Some(dial), first).map(Some)
+            }
+            Some(token) if token.kind == Kind::Star => {
+                let count = self.number::<u64>(first, "repeat count")?;
+                let open = self.expect(Kind::Open, "'(' after '*'")?;
+                if depth == MAX_NESTING {
+                    return Err(ParseError::new(open.span(), "repeats nested too deeply"));
+                }
+                let body = self.items(Some(open), depth + 1)?;
+                self.pos += 1;
+                Ok((count > 0 && !body.is_empty()).then_some(Item::Repeat { count, body }))
+            }
+            _ => Err(ParseError::new(
+                first.span(),
+                "expected ':' or '*' after a number",
+            )),
+        }
+    }
+
+    /// `L10`, `R10` or `=10`, after an optional dial prefix starting at
+    /// `first`
+    fn step<T: DialInt>(
+        &mut self,
+        dial: Option<usize>,
+        first: Token,
+    ) -> Result<Item<T>, ParseError> {
+        let direction = match self.peek() {
+            Some(token) if matches!(token.kind, Kind::Left | Kind::Right | Kind::Equals) => {
+                self.pos += 1;
+                token.kind
+            }
+            _ => return Err(ParseError::new(self.here(), "expected L, R or =")),
+        };
+        let value = self.expect(Kind::Number, "a number")?;
+        let magnitude = self.number::<T>(value, &format!("{} value", T::NAME))?;
+        let action = match direction {
+            Kind::Left => Action::Turn(-magnitude),
+            Kind::Right => Action::Turn(magnitude),
+            _ => Action::Set(magnitude),
+        };
+        Ok(Item::Step {
+            dial,
+            action,
+            span: first.start..value.end,
+            line: first.line,
+        })
+    }
+}
+
+/// A parsed instruction list, expanded on demand by `instructions`
+#[derive(Debug, Clone)]
+pub struct Program<'a, T> {
+    source: &'a [u8],
+    items: Vec<Item<T>>,
+}
+
+/// Parses `source`: instructions separated by whitespace, `#` comments,
+/// and `N*( ... )` repeat blocks
+pub fn parse<T: DialInt>(source: &[u8]) -> Result<Program<'_, T>, ParseError> {
+    let mut parser = Parser {
+        source,
+        tokens: tokenize(source)?,
+        pos: 0,
+    };
+    let items = parser.items(None, 0)?;
+    Ok(Program { source, items })
+}
+
+impl<'a, T: DialInt> Program<'a, T> {
+    /// Every instruction in order, repeats expanded lazily
+    pub fn instructions(&self) -> Instructions<'_, 'a, T> {
+        Instructions {
+            source: self.source,
+            stack: vec![(&self.items, 0, 0)],
+        }
+    }
+
+    /// Every instruction as written, each repeat block's body once
+    pub fn written(&self) -> Vec<Instruction<'a, T>> {
+        let mut written = Vec::new();
+        let mut pending: Vec<&Item<T>> = self.items.iter().rev().collect();
+        while let Some(item) = pending.pop() {
+            match item {
+                Item::Step { .. } => written.extend(item.instruction(self.source)),
+                Item::Repeat { body, .. } => pending.extend(body.iter().rev()),
+            }
+        }
+        written
+    }
+}
+
+/// Walks the program with a stack of (items, next index, passes left
+/// after this one), so memory follows nesting depth, not repeat counts
+pub struct Instructions<'p, 'a, T> {
+    source: &'a [u8],
+    stack: Vec<(&'p [Item<T>], usize, u64)>,
+}
+
+impl<'a, T: DialInt> Iterator for Instructions<'_, 'a, T> {
+    type Item = Instruction<'a, T>;
+
+    fn next(&mut self) -> Option<Instruction<'a, T>> {
+        loop {
+            let (items, pos, passes_left) = self.stack.last_mut()?;
+            let Some(item) = items.get(*pos) else {
+                if *passes_left > 0 {
+                    *passes_left -= 1;
+                    *pos = 0;
+                } else {
+                    self.stack.pop();
+                }
+                continue;
+            };
+            *pos += 1;
+
+            match item {
+                Item::Step { .. } => return item.instruction(self.source),
+                Item::Repeat { count, body } => self.stack.push((body, 0, count - 1)),
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use common::params::Params;
+use common::scan;
+use day01::dial::Policy;
+use day01::{occupancy, parse_input, tally, total, trace, what_if, DialInt, Setup, DEFAULTS};
+
+/// `--trace` or `--trace=FORMAT` on the command line
+fn trace_format() -> Option<Result<trace::Format, String>> {
+    std::env::args().skip(1).find_map(|arg| match arg.as_str() {
+        "--trace" => Some(Ok(trace::Format::Csv)),
+        _ => arg.strip_prefix("--trace=").map(trace::Format::parse),
+    })
+}
+
+/// `--what-if` or `--what-if=COUNT` on the command line, for the COUNT
+/// most influential steps (10 by default)
+fn what_if_count() -> Option<Result<usize, String>> {
+    std::env::args().skip(1).find_map(|arg| match arg.as_str() {
+        "--what-if" => Some(Ok(10)),
+        _ => arg.strip_prefix("--what-if=").map(|count| {
+            count
+                .parse()
+                .map_err(|_| format!("invalid --what-if count: {count}"))
+        }),
+    })
+}
+
+/// `--histogram` or `--histogram=STYLE` on the command line
+fn histogram_style() -> Option<Result<occupancy::Style, String>> {
+    std::env::args().skip(1).find_map(|arg| match arg.as_str() {
+        "--histogram" => Some(Ok(occupancy::Style::Table)),
+        _ => arg
+            .strip_prefix("--histogram=")
+            .map(occupancy::Style::parse),
+    })
+}
+
+fn main() {
+    let params = Params::load("day01", DEFAULTS).expect("Invalid parameters");
+    let content = scan::read_input("input.txt").expect("Failed to read input.txt");
+
+    match params.get::<u32>("bits").expect("Invalid parameters") {
+        32 => report::<i32>(&content, &params),
+        64 => report::<i64>(&content, &params),
+        128 => report::<i128>(&content, &params),
+        bits => {
+            eprintln!("Invalid parameters: bits must be 32, 64 or 128, got {bits}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn report<T: DialInt>(content: &[u8], params: &Params) {
+    if let Some(format) = trace_format() {
+        let format = format.expect("Invalid parameters");
+        let setup = Setup::<T>::from_params(params).expect("Invalid parameters");
+        let program = parse_input(content).expect("Invalid input");
+        setup.check(&program).expect("Invalid input");
+        let odometer = setup
+            .odometer(Policy::PassesThrough)
+            .expect("Invalid parameters");
+
+        let stdout = std::io::stdout();
+        trace::export(
+            trace::Trace::new(odometer, program.instructions()),
+            format,
+            &mut stdout.lock(),
+        )
+        .expect("Failed to write trace");
+        return;
+    }
+
+    if let Some(count) = what_if_count() {
+        let count = count.expect("Invalid parameters");
+        let setup = Setup::<T>::from_params(params).expect("Invalid parameters");
+        let program = parse_input(content).expect("Invalid input");
+        let dial = setup
+            .single_dial(&program, "what-if analysis")
+            .expect("Invalid input");
+
+        let influences = what_if::analyze(dial, program.instructions());
+        let stdout = std::io::stdout();
+        what_if::render(&what_if::top(&influences, count), &mut stdout.lock())
+            .expect("Failed to write what-if table");
+        return;
+    }
+
+    if let Some(style) = histogram_style() {
+        let style = style.expect("Invalid parameters");
+        let setup = Setup::<T>::from_params(params).expect("Invalid parameters");
+        let program = parse_input(content).expect("Invalid input");
+        let dial = setup
+            .single_dial(&program, "a histogram")
+            .expect("Invalid input");
+
+        let histogram =
+            occupancy::histogram(dial, program.instructions()).expect("Invalid parameters");
+        let stdout = std::io::stdout();
+        occupancy::render(&histogram, style, &mut stdout.lock())
+            .expect("Failed to write histogram");
+        return;
+    }
+
+    let setup = Setup::<T>::from_params(params).expect("Invalid parameters");
+    let program = parse_input(content).expect("Invalid input");
+    let landings = tally(&setup, &program, Policy::LandingsOn).expect("Invalid input");
+    let passes = tally(&setup, &program, Policy::PassesThrough).expect("Invalid input");
+    if landings.len() > 1 {
+        for (idx, (zeros, crossings)) in landings.iter().zip(&passes).enumerate() {
+            println!("Dial {}: {} zeros, {} crossings", idx + 1, zeros, crossings);
+        }
+    }
+    let zeros = total(&landings).expect("Invalid input");
+    let crossings = total(&passes).expect("Invalid input");
+
+    println!("{}", zeros);
+    println!("{}", crossings);
+}
@@ -0,0 +1,53 @@
+// Integer widths the dials can count in
+
+use common::scan::Int;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, Div, Neg, Rem, Sub};
+use std::str::FromStr;
+
+/// Signed integer widths the dial arithmetic can run in
+pub trait DialInt:
+    Int
+    + Ord
+    + Debug
+    + Display
+    + FromStr
+    + Hash
+    + Into<i128>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Neg<Output = Self>
+{
+    const ONE: Self;
+    const NAME: &'static str;
+
+    fn rem_euclid(self, rhs: Self) -> Self;
+    fn div_euclid(self, rhs: Self) -> Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_dial_int {
+    ($($t:ident),*) => {$(
+        impl DialInt for $t {
+            const ONE: Self = 1;
+            const NAME: &'static str = stringify!($t);
+
+            fn rem_euclid(self, rhs: Self) -> Self {
+                $t::rem_euclid(self, rhs)
+            }
+
+            fn div_euclid(self, rhs: Self) -> Self {
+                $t::div_euclid(self, rhs)
+            }
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                $t::checked_add(self, rhs)
+            }
+        }
+    )*};
+}
+
+impl_dial_int!(i32, i64, i128);
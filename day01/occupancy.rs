@@ -0,0 +1,132 @@
+// How often each dial position is landed on or passed through
+
+use super::*;
+use std::io::{self, Write};
+
+/// Larger dials would need a histogram too big to print or hold
+const MAX_SIZE: usize = 1 << 20;
+
+/// How often each position of a dial was landed on and clicked onto
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    /// Steps ending on each position, jumps included
+    pub landings: Vec<u64>,
+    /// Clicks leaving the dial on each position, as counted for marks
+    pub passes: Vec<u128>,
+}
+
+/// Tallies every step on `dial` with range arithmetic: a rotation adds
+/// its full revolutions to every position at once and its remainder as
+/// one or two ranges of a difference array, so the cost per step does
+/// not depend on the distance.
+pub fn histogram<'a, T: DialInt>(
+    mut dial: Dial<T>,
+    instructions: impl Iterator<Item = Instruction<'a, T>>,
+) -> Result<Histogram, String> {
+    let size = usize::try_from(dial.size().into())
+        .ok()
+        .filter(|&size| size <= MAX_SIZE)
+        .ok_or_else(|| format!("histograms need a dial size of at most {MAX_SIZE}"))?;
+    let index = |position: T| position.into() as usize;
+
+    let mut landings = vec![0; size];
+    let mut starts = vec![0i64; size + 1];
+    let mut revolutions = 0u128;
+    for instruction in instructions {
+        let movement = match instruction.action {
+            Action::Turn(movement) => movement,
+            Action::Set(position) => {
+                landings[index(dial.set(position).to)] += 1;
+                continue;
+            }
+        };
+        let events = dial.apply(movement);
+        landings[index(events.to)] += 1;
+
+        let distance = movement.into().unsigned_abs();
+        revolutions = revolutions
+            .checked_add(distance / size as u128)
+            .ok_or("pass count overflows u128")?;
+
+        // The remaining clicks, from just past `from` in the direction
+        // of travel and ending on `to`
+        let clicks = (distance % size as u128) as usize;
+        let first = if movement > T::ZERO {
+            (index(events.from) + 1) % size
+        } else {
+            index(events.to)
+        };
+        let end = first + clicks;
+        starts[first] += 1;
+        if end <= size {
+            starts[end] -= 1;
+        } else {
+            starts[0] += 1;
+            starts[end - size] -= 1;
+        }
+    }
+
+    let mut covered = 0;
+    let passes = starts[..size]
+        .iter()
+        .map(|&delta| {
+            covered += delta;
+            revolutions + covered as u128
+        })
+        .collect();
+    Ok(Histogram { landings, passes })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Table,
+    Bars,
+}
+
+impl Style {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "table" => Ok(Style::Table),
+            "bars" => Ok(Style::Bars),
+            _ => Err(format!(
+                "unknown histogram style {name:?}, expected table or bars"
+            )),
+        }
+    }
+}
+
+/// Width of the longest bar
+const BAR_WIDTH: u128 = 40;
+
+fn bar(count: u128, max: u128) -> String {
+    // Round up, so that every non-zero count shows
+    let len = (count * BAR_WIDTH).div_ceil(max.max(1));
+    "#".repeat(len as usize)
+}
+
+pub fn render(histogram: &Histogram, style: Style, out: &mut impl Write) -> io::Result<()> {
+    let rows = histogram.landings.iter().zip(&histogram.passes).enumerate();
+    match style {
+        Style::Table => {
+            writeln!(out, "{:>8} {:>10} {:>12}", "position", "landings", "passes")?;
+            for (position, (landings, passes)) in rows {
+                writeln!(out, "{position:>8} {landings:>10} {passes:>12}")?;
+            }
+        }
+        Style::Bars => {
+            let max_landings = histogram.landings.iter().copied().max().unwrap_or(0) as u128;
+            let max_passes = histogram.passes.iter().copied().max().unwrap_or(0);
+            let width = (histogram.landings.len() - 1).to_string().len();
+            for (position, (&landings, &passes)) in rows {
+                writeln!(
+                    out,
+                    "{position:>width$} | {:<bars$} {landings:>8} | {} {passes}",
+                    bar(landings as u128, max_landings),
+                    bar(passes, max_passes),
+                    bars = BAR_WIDTH as usize,
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
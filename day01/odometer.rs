@@ -0,0 +1,39 @@
+// Dials chained into an odometer
+
+use super::*;
+
+/// Dials chained lowest first: every full revolution of one dial turns
+/// the next one by a click, like the wheels of an odometer
+#[derive(Debug, Clone)]
+pub struct Odometer<T> {
+    dials: Vec<Dial<T>>,
+}
+
+impl<T: DialInt> Odometer<T> {
+    pub fn new(dials: Vec<Dial<T>>) -> Self {
+        Self { dials }
+    }
+
+    pub fn dials(&self) -> &[Dial<T>] {
+        &self.dials
+    }
+
+    /// Turns dial `index` (0-based) and ripples its carries upwards;
+    /// the top dial's carry is dropped. Returns what each dial did.
+    pub fn apply(&mut self, index: usize, action: Action<T>) -> Vec<(usize, Events<T>)> {
+        let mut movement = match action {
+            Action::Turn(movement) => movement,
+            Action::Set(position) => return vec![(index, self.dials[index].set(position))],
+        };
+        let mut moved = Vec::new();
+        for (index, dial) in self.dials.iter_mut().enumerate().skip(index) {
+            let events = dial.apply(movement);
+            moved.push((index, events));
+            movement = events.carry;
+            if movement == T::ZERO {
+                break;
+            }
+        }
+        moved
+    }
+}
@@ -0,0 +1,74 @@
+// Dial settings read from the params
+
+use super::*;
+
+/// Dial settings shared by every mode
+pub struct Setup<T> {
+    sizes: Vec<T>,
+    starts: Vec<T>,
+    marks: Vec<T>,
+}
+
+fn int_list<T: DialInt>(params: &Params, key: &str) -> Result<Vec<T>, String> {
+    params
+        .list(key)
+        .into_iter()
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("invalid {key} for {}: {value}", T::NAME))
+        })
+        .collect()
+}
+
+impl<T: DialInt> Setup<T> {
+    pub fn from_params(params: &Params) -> Result<Self, String> {
+        let setup = Self {
+            sizes: int_list(params, "size")?,
+            starts: int_list(params, "start")?,
+            marks: int_list(params, "marks")?,
+        };
+        if setup.sizes.is_empty() || setup.starts.len() > setup.sizes.len() {
+            return Err("need a size for every dial, and at most one start each".to_string());
+        }
+        Ok(setup)
+    }
+
+    /// A dial per size, lowest first; dials without a start begin at 0
+    pub fn odometer(&self, policy: fn(Vec<T>) -> Policy<T>) -> Result<Odometer<T>, String> {
+        self.sizes
+            .iter()
+            .enumerate()
+            .map(|(idx, &size)| {
+                let start = self.starts.get(idx).copied().unwrap_or(T::ZERO);
+                Dial::new(size, start, policy(self.marks.clone()))
+            })
+            .collect::<Result<_, _>>()
+            .map(Odometer::new)
+    }
+
+    pub fn check(&self, program: &Program<T>) -> Result<(), String> {
+        match program
+            .written()
+            .into_iter()
+            .find(|instruction| instruction.dial_index() >= self.sizes.len())
+        {
+            Some(instruction) => Err(format!(
+                "line {}: there are only {} dials",
+                instruction.line,
+                self.sizes.len()
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The one dial of an analysis that follows a single dial, once every
+    /// step of `program` is known to address it
+    pub fn single_dial(&self, program: &Program<T>, analysis: &str) -> Result<Dial<T>, String> {
+        self.check(program)?;
+        match self.odometer(Policy::LandingsOn)?.dials() {
+            [dial] => Ok(dial.clone()),
+            _ => Err(format!("{analysis} needs a single dial")),
+        }
+    }
+}
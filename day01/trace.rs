@@ -0,0 +1,138 @@
+// Step-by-step traces of a program, exported as CSV or JSON
+
+use super::*;
+use common::json;
+use std::io::{self, Write};
+
+/// One rotation as it played out on one dial; a carry into the next
+/// dial is a step of its own, with the same line and instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step<'a, T> {
+    pub line: usize,
+    pub instruction: &'a [u8],
+    /// 1-based, lowest first
+    pub dial: usize,
+    pub from: T,
+    pub to: T,
+    pub landed: bool,
+    pub crossings: T,
+}
+
+/// Replays instructions on an odometer of pass-counting dials
+pub struct Trace<'a, T, I> {
+    odometer: Odometer<T>,
+    instructions: I,
+    pending: std::vec::IntoIter<Step<'a, T>>,
+}
+
+impl<'a, T, I> Trace<'a, T, I> {
+    /// Every instruction must address one of the odometer's dials
+    pub fn new(odometer: Odometer<T>, instructions: I) -> Self {
+        Self {
+            odometer,
+            instructions,
+            pending: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<'a, T: DialInt, I: Iterator<Item = Instruction<'a, T>>> Iterator for Trace<'a, T, I> {
+    type Item = Step<'a, T>;
+
+    fn next(&mut self) -> Option<Step<'a, T>> {
+        if let Some(step) = self.pending.next() {
+            return Some(step);
+        }
+
+        let instruction = self.instructions.next()?;
+        let steps: Vec<Step<'a, T>> = self
+            .odometer
+            .apply(instruction.dial_index(), instruction.action)
+            .into_iter()
+            .map(|(index, events)| Step {
+                line: instruction.line,
+                instruction: instruction.text,
+                dial: index + 1,
+                from: events.from,
+                to: events.to,
+                landed: events.landed,
+                crossings: events.passes,
+            })
+            .collect();
+        self.pending = steps.into_iter();
+        self.pending.next()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!(
+                "unknown trace format {name:?}, expected csv or json"
+            )),
+        }
+    }
+}
+
+/// Writes one record per step, in a stable layout meant for diffing
+pub fn export<'a, T: DialInt>(
+    steps: impl Iterator<Item = Step<'a, T>>,
+    format: Format,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    match format {
+        Format::Csv => {
+            writeln!(out, "line,instruction,dial,from,to,landed,crossings")?;
+            for step in steps {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{}",
+                    step.line,
+                    csv_field(&scan::show(step.instruction)),
+                    step.dial,
+                    step.from,
+                    step.to,
+                    step.landed,
+                    step.crossings
+                )?;
+            }
+        }
+        Format::Json => {
+            writeln!(out, "[")?;
+            for (idx, step) in steps.enumerate() {
+                if idx > 0 {
+                    writeln!(out, ",")?;
+                }
+                write!(
+                    out,
+                    "  {{\"line\": {}, \"instruction\": {}, \"dial\": {}, \"from\": {}, \"to\": {}, \"landed\": {}, \"crossings\": {}}}",
+                    step.line,
+                    json::string(&scan::show(step.instruction)),
+                    step.dial,
+                    step.from,
+                    step.to,
+                    step.landed,
+                    step.crossings
+                )?;
+            }
+            writeln!(out, "\n]")?;
+        }
+    }
+    Ok(())
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,139 @@
+// Which instructions the answers are most sensitive to
+
+use super::*;
+use common::hash::FxHashMap;
+use std::cmp::Reverse;
+use std::io::{self, Write};
+
+/// How the landing count would change if one step were edited, and
+/// what the step contributes as it stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Influence<'a, T> {
+    /// 1-based position in the expanded program
+    pub step: usize,
+    pub instruction: Instruction<'a, T>,
+    pub crossings: T,
+    /// Change in landings with L and R swapped; `None` for jumps
+    pub flipped: Option<i64>,
+    /// Change in landings without the step
+    pub removed: i64,
+}
+
+impl<T> Influence<'_, T> {
+    pub fn largest_change(&self) -> i64 {
+        self.removed.abs().max(self.flipped.map_or(0, i64::abs))
+    }
+}
+
+/// `(a + b) % size` for `a` and `b` in `0..size`, without overflow
+fn add_mod<T: DialInt>(a: T, b: T, size: T) -> T {
+    if b >= size - a {
+        b - (size - a)
+    } else {
+        a + b
+    }
+}
+
+/// Every step's influence on a single dial, in program order.
+///
+/// Editing a step moves every later position by the same amount, up to
+/// the next jump. So one pass from the back, counting the positions
+/// still to come in the current stretch between jumps, answers each
+/// edit with a lookup per mark instead of a replay.
+pub fn analyze<'a, T: DialInt>(
+    mut dial: Dial<T>,
+    instructions: impl Iterator<Item = Instruction<'a, T>>,
+) -> Vec<Influence<'a, T>> {
+    let steps: Vec<(Instruction<'a, T>, Events<T>)> = instructions
+        .map(|instruction| {
+            let events = match instruction.action {
+                Action::Turn(movement) => dial.apply(movement),
+                Action::Set(position) => dial.set(position),
+            };
+            (instruction, events)
+        })
+        .collect();
+
+    let size = dial.size();
+    let marks = dial.marks();
+    let mut later: FxHashMap<T, i64> = FxHashMap::default();
+    // Later landings if every later position moved back by `shift`
+    let landings_after = |later: &FxHashMap<T, i64>, shift: T| -> i64 {
+        marks
+            .iter()
+            .map(|&mark| later.get(&add_mod(mark, shift, size)).copied().unwrap_or(0))
+            .sum()
+    };
+
+    let mut influences = Vec::with_capacity(steps.len());
+    for (idx, (instruction, events)) in steps.iter().enumerate().rev() {
+        let unchanged = landings_after(&later, T::ZERO);
+        let landed = events.landed as i64;
+
+        let moved = normalize_dial(events.to - events.from, size);
+        let removed = landings_after(&later, moved) - unchanged - landed;
+        let flipped = match instruction.action {
+            Action::Turn(movement) => {
+                let clicks = normalize_dial(movement, size);
+                let twice = add_mod(clicks, clicks, size);
+                let to = normalize_dial(events.to - twice, size);
+                let now_landed = marks.contains(&to) as i64;
+                Some(landings_after(&later, twice) - unchanged + now_landed - landed)
+            }
+            Action::Set(_) => None,
+        };
+
+        influences.push(Influence {
+            step: idx + 1,
+            instruction: *instruction,
+            crossings: events.passes,
+            flipped,
+            removed,
+        });
+
+        // Nothing before a jump can move it or the positions after it
+        match instruction.action {
+            Action::Turn(_) => *later.entry(events.to).or_insert(0) += 1,
+            Action::Set(_) => later.clear(),
+        }
+    }
+    influences.reverse();
+    influences
+}
+
+/// The `count` steps whose edits change the landing count the most,
+/// earliest first among equals
+pub fn top<'a, T>(influences: &[Influence<'a, T>], count: usize) -> Vec<Influence<'a, T>>
+where
+    T: Copy,
+{
+    let mut ranked = influences.to_vec();
+    ranked.sort_by_key(|influence| (Reverse(influence.largest_change()), influence.step));
+    ranked.truncate(count);
+    ranked
+}
+
+/// Writes `influences` as a table, one step per row
+pub fn render<T: DialInt>(influences: &[Influence<T>], out: &mut impl Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:>8} {:>6}  {:<16} {:>10} {:>8} {:>8}",
+        "step", "line", "instruction", "crossings", "flipped", "removed"
+    )?;
+    for influence in influences {
+        let flipped = influence
+            .flipped
+            .map_or("-".to_string(), |delta| format!("{delta:+}"));
+        writeln!(
+            out,
+            "{:>8} {:>6}  {:<16} {:>10} {:>8} {:>+8}",
+            influence.step,
+            influence.instruction.line,
+            scan::show(influence.instruction.text),
+            influence.crossings,
+            flipped,
+            influence.removed
+        )?;
+    }
+    Ok(())
+}
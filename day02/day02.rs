@@ -1,67 +1,478 @@
-use std::collections::HashSet;
-use std::fs;
+mod id;
+mod invalid_ids;
 
-fn parse_input(input: &str) -> Vec<(i64, i64)> {
-    input
-        .trim()
-        .trim_end_matches(',')
-        .split(',')
+use algo::ranges::merge_ranges;
+use common::minimize::{keeper, Units};
+use common::scan;
+use common::uint::U256;
+pub use id::Id;
+pub use invalid_ids::InvalidIds;
+use std::fmt;
+
+pub const SOLVER_VERSION: &str = "1";
+
+/// Why the answers could not be computed in a given `Id` type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdError {
+    /// An ID or a sum does not fit; a wider type may still do
+    Overflow(String),
+    Malformed(String),
+}
+
+impl fmt::Display for IdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdError::Overflow(message) | IdError::Malformed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<IdError> for String {
+    fn from(err: IdError) -> Self {
+        err.to_string()
+    }
+}
+
+fn malformed(range: &[u8]) -> IdError {
+    IdError::Malformed(format!("expected start-stop: {:?}", scan::show(range)))
+}
+
+fn parse_id<T: Id>(bytes: &[u8], range: &[u8]) -> Result<T, IdError> {
+    scan::parse(bytes).ok_or_else(|| {
+        if !bytes.is_empty() && bytes.iter().all(u8::is_ascii_digit) {
+            IdError::Overflow(format!(
+                "ID does not fit in {}: {:?}",
+                T::name(),
+                scan::show(bytes)
+            ))
+        } else {
+            malformed(range)
+        }
+    })
+}
+
+pub fn parse_input<T: Id>(input: &[u8]) -> Result<Vec<(T, T)>, IdError> {
+    let input = input.trim_ascii();
+    let trailing_commas = input.iter().rev().take_while(|&&b| b == b',').count();
+
+    input[..input.len() - trailing_commas]
+        .split(|&b| b == b',')
         .map(|range| {
-            let (start, stop) = range.split_once('-').unwrap();
-            (start.parse().unwrap(), stop.parse().unwrap())
+            let (start, stop) = scan::split_once(range, b'-').ok_or_else(|| malformed(range))?;
+            Ok((parse_id(start, range)?, parse_id(stop, range)?))
         })
         .collect()
 }
 
-fn find_invalid_ids(start: i64, stop: i64, exactly_two_reps: bool) -> Vec<i64> {
-    let start_digits = start.to_string().len();
-    let stop_digits = stop.to_string().len();
-
-    (start_digits..=stop_digits)
-        .flat_map(|digit_count| {
-            (1..=digit_count / 2).filter_map(move |pattern_len| {
-                if digit_count % pattern_len != 0 {
-                    return None;
-                }
-                let reps = digit_count / pattern_len;
-                if exactly_two_reps && reps != 2 {
-                    return None;
-                }
-                if !exactly_two_reps && reps < 2 {
-                    return None;
-                }
-
-                let min_pattern = if pattern_len == 1 { 1 } else { 10_i64.pow((pattern_len - 1) as u32) };
-                let max_pattern = 10_i64.pow(pattern_len as u32) - 1;
-
-                Some((min_pattern..=max_pattern).filter_map(move |pattern| {
-                    let full_str = pattern.to_string().repeat(reps);
-                    let full_num: i64 = full_str.parse().unwrap();
-                    if full_num >= start && full_num <= stop {
-                        Some(full_num)
-                    } else {
-                        None
-                    }
-                }))
-            })
-            .flatten()
+fn digit_count<T: Id>(n: T) -> u32 {
+    n.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// The `period`-digit patterns that repeat into `digits`-digit numbers in
+/// `start..=stop`, as `(repunit, lowest, highest)`. Each such number is the
+/// pattern times the repunit 1_0..01_0..01, so the patterns form an
+/// interval. `None` if there are none, or none that fit in `T`.
+fn patterns<T: Id>(start: T, stop: T, digits: u32, period: u32) -> Option<(T, T, T)> {
+    let (zero, one) = (T::from(0), T::from(1));
+    let shift = T::from(10).checked_pow(period)?;
+    let repunit = (1..digits / period).try_fold(one, |repunit, _| {
+        repunit.checked_mul(shift)?.checked_add(one)
+    })?;
+
+    let round_up = if start % repunit == zero { zero } else { one };
+    let lowest = (shift / T::from(10)).max(start / repunit + round_up);
+    let highest = (shift - one).min(stop / repunit);
+    (lowest <= highest).then_some((repunit, lowest, highest))
+}
+
+/// Möbius function, for the small repetition counts of a digit string
+fn mobius(mut n: u32) -> i32 {
+    let mut sign = 1;
+    let mut factor = 2;
+    while n > 1 {
+        if n.is_multiple_of(factor) {
+            n /= factor;
+            if n.is_multiple_of(factor) {
+                return 0;
+            }
+            sign = -sign;
+        }
+        factor += 1;
+    }
+    sign
+}
+
+/// The periods a `digits`-digit invalid ID can repeat with, and whether
+/// each one's IDs are added or subtracted to count every ID once.
+///
+/// A number with two periods dividing its length also has their gcd as a
+/// period, so taking repetition counts with Möbius signs is exact.
+fn periods(digits: u32, exactly_two_reps: bool) -> impl Iterator<Item = (u32, bool)> {
+    (2..=digits)
+        .filter(move |&reps| digits.is_multiple_of(reps))
+        .filter_map(move |reps| {
+            let sign = if exactly_two_reps {
+                (reps == 2) as i32
+            } else {
+                -mobius(reps)
+            };
+            (sign != 0).then_some((digits / reps, sign > 0))
         })
-        .collect()
 }
 
-fn solve(ranges: &[(i64, i64)], exactly_two_reps: bool) -> i64 {
-    let all_invalid: HashSet<i64> = ranges
+/// Adds up `term(repunit, lowest, highest)` over the pattern intervals of
+/// `start..=stop`, with inclusion–exclusion signs. `None` on overflow.
+fn over_patterns<T: Id>(
+    start: T,
+    stop: T,
+    exactly_two_reps: bool,
+    term: impl Fn(T, T, T) -> Option<T>,
+) -> Option<T> {
+    // Added and subtracted terms are kept apart, as `T` is unsigned
+    let (mut added, mut subtracted) = (T::from(0), T::from(0));
+    for digits in digit_count(start)..=digit_count(stop) {
+        for (period, add) in periods(digits, exactly_two_reps) {
+            let Some((repunit, lowest, highest)) = patterns(start, stop, digits, period) else {
+                continue;
+            };
+            let value = term(repunit, lowest, highest)?;
+            if add {
+                added = added.checked_add(value)?;
+            } else {
+                subtracted = subtracted.checked_add(value)?;
+            }
+        }
+    }
+    Some(added - subtracted)
+}
+
+/// Sum of the IDs in `start..=stop` made of a pattern repeated twice, or
+/// at least twice: an arithmetic series per pattern interval. `None` if it
+/// overflows.
+fn sum_invalid_ids<T: Id>(start: T, stop: T, exactly_two_reps: bool) -> Option<T> {
+    over_patterns(start, stop, exactly_two_reps, |repunit, lowest, highest| {
+        // Halve whichever factor is even before multiplying
+        let two = T::from(2);
+        let (count, ends) = (highest - lowest + T::from(1), lowest + highest);
+        let patterns = if count % two == T::from(0) {
+            (count / two).checked_mul(ends)?
+        } else {
+            count.checked_mul(ends / two)?
+        };
+        patterns.checked_mul(repunit)
+    })
+}
+
+fn count_overflow<T: Id>() -> IdError {
+    IdError::Overflow(format!("count of invalid IDs overflows {}", T::name()))
+}
+
+/// How many invalid IDs there are in `start..=stop`. They are about the
+/// square root of the numbers they repeat into, so the count fits in any
+/// `T` the IDs fit in; the overflow is checked all the same.
+fn count_invalid_ids<T: Id>(start: T, stop: T, exactly_two_reps: bool) -> Result<T, IdError> {
+    over_patterns(start, stop, exactly_two_reps, |_, lowest, highest| {
+        Some(highest - lowest + T::from(1))
+    })
+    .ok_or_else(count_overflow::<T>)
+}
+
+/// The smallest invalid ID that is at least `from`, if one fits in `T`
+fn next_invalid_id<T: Id>(from: T, exactly_two_reps: bool) -> Option<T> {
+    let mut from = from;
+    for digits in digit_count(from)..=digit_count(T::MAX) {
+        let first = periods(digits, exactly_two_reps)
+            .filter_map(|(period, _)| patterns(from, T::MAX, digits, period))
+            .map(|(repunit, lowest, _)| lowest * repunit)
+            .min();
+        if first.is_some() {
+            return first;
+        }
+        from = T::from(10).checked_pow(digits)?;
+    }
+    None
+}
+
+fn solve<T: Id>(ranges: &[(T, T)], exactly_two_reps: bool) -> Result<T, IdError> {
+    // Disjoint ranges, so no ID is summed twice
+    merge_ranges(ranges)
         .iter()
-        .flat_map(|&(start, stop)| find_invalid_ids(start, stop, exactly_two_reps))
-        .collect();
+        .try_fold(T::from(0), |total, &(start, stop)| {
+            total.checked_add(sum_invalid_ids(start, stop, exactly_two_reps)?)
+        })
+        .ok_or_else(|| IdError::Overflow(format!("sum of invalid IDs overflows {}", T::name())))
+}
 
-    all_invalid.iter().sum()
+/// The widest type the answers are computed in
+pub type Wide = U256;
+
+/// Computes the answers still missing in `T`, leaving the ones that overflow
+/// it for a wider type. The input is only parsed if any are missing.
+fn fill<T: Id + Into<Wide>>(
+    input: &[u8],
+    exactly_two_reps: &[bool],
+    answers: &mut [Result<Wide, IdError>],
+) -> Result<(), String> {
+    if answers.iter().all(Result::is_ok) {
+        return Ok(());
+    }
+    let ranges = match parse_input::<T>(input) {
+        Ok(ranges) => ranges,
+        Err(err @ IdError::Overflow(_)) => {
+            for answer in answers.iter_mut().filter(|answer| answer.is_err()) {
+                *answer = Err(err.clone());
+            }
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+    for (answer, &exactly_two_reps) in answers.iter_mut().zip(exactly_two_reps) {
+        if answer.is_err() {
+            *answer = solve(&ranges, exactly_two_reps).map(Into::into);
+        }
+    }
+    Ok(())
+}
+
+/// Answers for `input`, one per entry of `exactly_two_reps`, computed in
+/// u64 unless the IDs or a sum need u128 or, past that, `Wide`; anything
+/// wider is an overflow error. The input is parsed once per width it is
+/// needed in.
+fn widened(input: &[u8], exactly_two_reps: &[bool]) -> Result<Vec<Wide>, String> {
+    let mut answers = vec![Err(IdError::Overflow(String::new())); exactly_two_reps.len()];
+    fill::<u64>(input, exactly_two_reps, &mut answers)?;
+    fill::<u128>(input, exactly_two_reps, &mut answers)?;
+    fill::<Wide>(input, exactly_two_reps, &mut answers)?;
+    answers
+        .into_iter()
+        .map(|answer| answer.map_err(String::from))
+        .collect()
+}
+
+pub fn part1(input: &[u8]) -> Result<Wide, String> {
+    Ok(widened(input, &[true])?[0])
+}
+
+pub fn part2(input: &[u8]) -> Result<Wide, String> {
+    Ok(widened(input, &[false])?[0])
+}
+
+/// Both answers for `input`
+pub fn run(input: &[u8]) -> Result<(Wide, Wide), String> {
+    match widened(input, &[true, false])?[..] {
+        [part1, part2] => Ok((part1, part2)),
+        _ => unreachable!("one answer per part"),
+    }
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_input::<Wide>(input)
+        .map(drop)
+        .map_err(|err| err.to_string())
+}
+
+/// Minimizer units: the comma-separated ranges
+pub struct Ranges;
+
+fn ranges(input: &[u8]) -> impl Iterator<Item = &[u8]> {
+    input
+        .trim_ascii()
+        .split(|&b| b == b',')
+        .filter(|range| !range.is_empty())
+}
+
+impl Units for Ranges {
+    fn count(&self, input: &[u8]) -> usize {
+        ranges(input).count()
+    }
+
+    fn keep(&self, input: &[u8], kept: &[usize]) -> Vec<u8> {
+        let mut keep = keeper(kept);
+        let kept: Vec<&[u8]> = ranges(input).filter(|_| keep()).collect();
+        kept.join(&b',')
+    }
 }
 
-fn main() {
-    let input = fs::read_to_string("input.txt").expect("Failed to read input.txt");
-    let ranges = parse_input(&input);
+/// This day's minimizer passes
+pub const UNITS: &[&dyn Units] = &[&Ranges];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+    use common::minimize::{disagree, minimize};
+    use std::ops::RangeInclusive;
+
+    fn answer<T: Id>(input: &[u8], exactly_two_reps: bool) -> Result<T, IdError> {
+        solve(&parse_input(input)?, exactly_two_reps)
+    }
+
+    /// Whether `id` is a pattern repeated, by comparing digit strings
+    fn is_invalid(id: impl ToString, exactly_two_reps: bool) -> bool {
+        let digits = id.to_string();
+        (1..=digits.len() / 2)
+            .filter(|&len| digits.len().is_multiple_of(len))
+            .filter(|&len| !exactly_two_reps || digits.len() == 2 * len)
+            .any(|len| digits[..len].repeat(digits.len() / len) == digits)
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let example = parse_input::<u64>(include_bytes!("test_input.txt")).unwrap();
+        let overlapping = vec![
+            (0, 1200),
+            (95, 115),
+            (998, 1012),
+            (2_121_212_110, 2_121_212_130),
+        ];
+        for ranges in [example, overlapping] {
+            for exactly_two_reps in [true, false] {
+                let expected: u64 = merge_ranges(&ranges)
+                    .iter()
+                    .flat_map(|&(start, stop)| start..=stop)
+                    .filter(|&id| is_invalid(id, exactly_two_reps))
+                    .sum();
+                assert_eq!(solve(&ranges, exactly_two_reps), Ok(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn test_shrink_ranges() {
+        // Parts 1 and 2 only disagree on IDs of three or more repeats, like
+        // 4444444
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(
+            minimize(input, UNITS, disagree(part1, part2)),
+            b"4358832-4497315"
+        );
+    }
+
+    #[test]
+    fn test_invalid_ids() {
+        let ranges = parse_input::<u64>(include_bytes!("test_input.txt")).unwrap();
+        for exactly_two_reps in [true, false] {
+            let expected: Vec<u64> = merge_ranges(&ranges)
+                .iter()
+                .flat_map(|&(start, stop)| start..=stop)
+                .filter(|&id| is_invalid(id, exactly_two_reps))
+                .collect();
+            let ids = InvalidIds::new(&ranges, exactly_two_reps);
+            assert_eq!(ids.clone().collect::<Vec<_>>(), expected);
+
+            for (idx, &id) in expected.iter().enumerate() {
+                assert_eq!(ids.clone().nth(idx), Some(id));
+                let mut seeking = ids.clone();
+                seeking.seek(id - 1);
+                assert_eq!(seeking.next(), Some(id));
+            }
+            let mut skipping = ids.clone();
+            assert_eq!(skipping.nth(1), expected.get(1).copied());
+            assert_eq!(skipping.next(), expected.get(2).copied());
+
+            let in_range = |range: RangeInclusive<u64>| {
+                expected.iter().filter(|id| range.contains(id)).count() as u64
+            };
+            for range in [0..=u64::MAX, 20..=1000, 9000..=4_400_000] {
+                assert_eq!(ids.count_in(range.clone()), Ok(in_range(range)));
+            }
+        }
+
+        // No ranges to bound it: the first thousand of the whole number line
+        let everything = InvalidIds::new(&[(0, u128::MAX)], false);
+        let first: Vec<u128> = (1..)
+            .filter(|&id: &u128| is_invalid(id, false))
+            .take(1000)
+            .collect();
+        assert_eq!(everything.clone().take(1000).collect::<Vec<_>>(), first);
+        assert_eq!(everything.clone().nth(999), Some(first[999]));
+        let mut last = everything.clone();
+        last.seek(u128::MAX);
+        assert_eq!(last.next(), None);
+
+        let everything = InvalidIds::new(&[(Wide::ZERO, Wide::MAX)], false);
+        let first: Vec<Wide> = first[..100].iter().map(|&id| Wide::from(id)).collect();
+        assert_eq!(everything.clone().take(100).collect::<Vec<_>>(), first);
+        let bounded = InvalidIds::new(&[(Wide::ZERO, Wide::from(1_000_000u32))], false);
+        assert_eq!(bounded.clone().nth(99), Some(first[99]));
+        assert_eq!(
+            everything.count_in(Wide::ZERO..=first[99]),
+            Ok(Wide::from(100u8))
+        );
+    }
+
+    #[test]
+    fn test_wide_ids() {
+        // "123" ten times is also "123123123123123" twice
+        let id: u128 = "123".repeat(10).parse().unwrap();
+        let input = format!("{}-{}", id - 5, id + 5);
+        assert_eq!(
+            answer::<u64>(input.as_bytes(), true),
+            Err(IdError::Overflow(format!(
+                "ID does not fit in u64: \"{}\"",
+                id - 5
+            )))
+        );
+        assert_eq!(run(input.as_bytes()), Ok((Wide::from(id), Wide::from(id))));
+
+        // Every doubled pattern below 10^19: the IDs fit in u64, the sum only
+        // in u128
+        let doubled: u128 = (1..=9)
+            .map(|len| {
+                let (lowest, highest) = (10u128.pow(len - 1), 10u128.pow(len) - 1);
+                (highest + 2) * (lowest + highest) * (highest - lowest + 1) / 2
+            })
+            .sum();
+        let input = format!("1-{}", "9".repeat(19));
+        assert_eq!(
+            answer::<u64>(input.as_bytes(), true),
+            Err(IdError::Overflow(
+                "sum of invalid IDs overflows u64".to_string()
+            ))
+        );
+        assert_eq!(
+            run(input.as_bytes()).map(|(part1, _)| part1),
+            Ok(Wide::from(doubled))
+        );
+
+        // Past u128, in `Wide`: the same sum up to 10^39, and 42 digits of
+        // "123" which are also 21 of them twice
+        let doubled = (1..=19).fold(Wide::ZERO, |sum, len| {
+            let pow = |exp| Wide::from(10u8).checked_pow(exp).unwrap();
+            let (lowest, highest) = (pow(len - 1), pow(len) - Wide::from(1u8));
+            let count = highest - lowest + Wide::from(1u8);
+            sum + (highest + Wide::from(2u8)) * (lowest + highest) * count / Wide::from(2u8)
+        });
+        let input = format!("1-{}", "9".repeat(39));
+        assert_eq!(run(input.as_bytes()).map(|(part1, _)| part1), Ok(doubled));
+        let id: Wide = "123".repeat(14).parse().unwrap();
+        let input = format!("{}-{}", id - Wide::from(5u8), id + Wide::from(5u8));
+        assert_eq!(run(input.as_bytes()), Ok((id, id)));
+
+        let too_long = "9".repeat(78);
+        assert_eq!(
+            run(format!("1-{too_long}").as_bytes()),
+            Err(format!("ID does not fit in u256: \"{too_long}\""))
+        );
+        // Only overflow is worth retrying in u128, not syntax errors
+        assert_eq!(
+            answer::<u64>(b"11-22,95", true),
+            Err(IdError::Malformed(
+                "expected start-stop: \"95\"".to_string()
+            ))
+        );
+        assert_eq!(
+            run(b"11-22,95"),
+            Err("expected start-stop: \"95\"".to_string())
+        );
+    }
 
-    println!("Part 1: {}", solve(&ranges, true));
-    println!("Part 2: {}", solve(&ranges, false));
+    #[test]
+    fn fuzz_parse_input() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(parse_input::<u64>);
+    }
 }
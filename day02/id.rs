@@ -0,0 +1,85 @@
+// Integer widths the IDs and their sums can be computed in
+
+use common::scan::Int;
+use common::uint::Uint;
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// Unsigned integer widths the IDs and their sums can be computed in
+pub trait Id:
+    Int
+    + Ord
+    + Debug
+    + Display
+    + From<u8>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const MAX: Self;
+
+    /// The type's name, for errors
+    fn name() -> String;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+    fn checked_ilog10(self) -> Option<u32>;
+}
+
+macro_rules! impl_id {
+    ($($t:ident),*) => {$(
+        impl Id for $t {
+            const MAX: Self = $t::MAX;
+
+            fn name() -> String {
+                stringify!($t).to_string()
+            }
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                $t::checked_add(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                $t::checked_mul(self, rhs)
+            }
+
+            fn checked_pow(self, exp: u32) -> Option<Self> {
+                $t::checked_pow(self, exp)
+            }
+
+            fn checked_ilog10(self) -> Option<u32> {
+                $t::checked_ilog10(self)
+            }
+        }
+    )*};
+}
+
+impl_id!(u64, u128);
+
+/// Any width past u128, `u{BITS}` in errors
+impl<const LIMBS: usize> Id for Uint<LIMBS> {
+    const MAX: Self = Self::MAX;
+
+    fn name() -> String {
+        format!("u{}", Self::BITS)
+    }
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Uint::checked_add(self, rhs)
+    }
+
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Uint::checked_mul(self, rhs)
+    }
+
+    fn checked_pow(self, exp: u32) -> Option<Self> {
+        Uint::checked_pow(self, exp)
+    }
+
+    fn checked_ilog10(self) -> Option<u32> {
+        Uint::checked_ilog10(self)
+    }
+}
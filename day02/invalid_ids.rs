@@ -0,0 +1,114 @@
+// A lazy, ascending iterator over the invalid IDs of a set of ranges
+
+use super::*;
+use std::ops::RangeInclusive;
+
+/// The invalid IDs in a set of ranges, in ascending order and each
+/// once. Only the merged ranges are stored; every step is worked out
+/// from the pattern intervals.
+#[derive(Debug, Clone)]
+pub struct InvalidIds<T> {
+    ranges: Vec<(T, T)>,
+    exactly_two_reps: bool,
+    /// Index of the range holding `next`
+    range: usize,
+    /// Where to resume looking; past the end once `range` is
+    next: T,
+}
+
+impl<T: Id> InvalidIds<T> {
+    pub fn new(ranges: &[(T, T)], exactly_two_reps: bool) -> Self {
+        Self {
+            ranges: merge_ranges(ranges),
+            exactly_two_reps,
+            range: 0,
+            next: T::from(0),
+        }
+    }
+
+    /// Moves to just past `after`, so the next ID is the first one
+    /// greater than it. Seeking backwards is allowed.
+    pub fn seek(&mut self, after: T) {
+        match after.checked_add(T::from(1)) {
+            Some(next) => {
+                self.next = next;
+                self.range = self.ranges.partition_point(|&(_, stop)| stop < next);
+            }
+            None => self.range = self.ranges.len(),
+        }
+    }
+
+    /// How many of the IDs fall in `range`, wherever the iterator is
+    pub fn count_in(&self, range: RangeInclusive<T>) -> Result<T, IdError> {
+        self.ranges
+            .iter()
+            .try_fold(T::from(0), |total, &(start, stop)| {
+                let start = start.max(*range.start());
+                let stop = stop.min(*range.end());
+                if start > stop {
+                    return Ok(total);
+                }
+                let count = count_invalid_ids(start, stop, self.exactly_two_reps)?;
+                total.checked_add(count).ok_or_else(count_overflow::<T>)
+            })
+    }
+
+    /// Where the current range resumes, if there is one
+    fn current(&self) -> Option<(T, T)> {
+        let &(start, stop) = self.ranges.get(self.range)?;
+        Some((start.max(self.next), stop))
+    }
+
+    fn advance_past(&mut self, id: T) {
+        match id.checked_add(T::from(1)) {
+            Some(next) => self.next = next,
+            None => self.range = self.ranges.len(),
+        }
+    }
+}
+
+impl<T: Id + TryFrom<usize>> Iterator for InvalidIds<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some((start, stop)) = self.current() {
+            match next_invalid_id(start, self.exactly_two_reps) {
+                Some(id) if id <= stop => {
+                    self.advance_past(id);
+                    return Some(id);
+                }
+                _ => self.range += 1,
+            }
+        }
+        None
+    }
+
+    /// Skips whole ranges by counting, then bisects on the count
+    /// within the range that holds the answer
+    fn nth(&mut self, n: usize) -> Option<T> {
+        let mut n = T::try_from(n).ok()?;
+        while let Some((start, stop)) = self.current() {
+            // A count that overflows `T` can only end the iteration
+            let count = count_invalid_ids(start, stop, self.exactly_two_reps).ok()?;
+            if n >= count {
+                n = n - count;
+                self.range += 1;
+                continue;
+            }
+
+            // The smallest `id` with more than `n` IDs in `start..=id`
+            let (mut low, mut high) = (start, stop);
+            while low < high {
+                let mid = low + (high - low) / T::from(2);
+                if count_invalid_ids(start, mid, self.exactly_two_reps).ok()? > n {
+                    high = mid;
+                } else {
+                    low = mid + T::from(1);
+                }
+            }
+            self.advance_past(low);
+            return Some(low);
+        }
+        None
+    }
+}
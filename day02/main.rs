@@ -0,0 +1,50 @@
+use common::scan;
+use day02::{parse_input, run, InvalidIds, Wide};
+
+/// `--next=X`, `--count=A-B` or `--nth=N`: the first invalid ID (as in
+/// part 2) above X, how many there are in A..=B, or the Nth counting from 0
+fn query(ids: &mut InvalidIds<Wide>, arg: &str) -> Result<String, String> {
+    let number = |text: &str| {
+        text.parse::<Wide>()
+            .map_err(|_| format!("invalid number in {arg:?}"))
+    };
+    let found = |id: Option<Wide>| id.map_or("none".to_string(), |id| id.to_string());
+
+    match arg.split_once('=') {
+        Some(("--next", after)) => {
+            ids.seek(number(after)?);
+            Ok(found(ids.next()))
+        }
+        Some(("--count", range)) => {
+            let (start, stop) = range
+                .split_once('-')
+                .ok_or_else(|| format!("expected --count=A-B, got {arg:?}"))?;
+            let count = ids.count_in(number(start)?..=number(stop)?)?;
+            Ok(count.to_string())
+        }
+        Some(("--nth", n)) => {
+            let n = n.parse().map_err(|_| format!("invalid index in {arg:?}"))?;
+            ids.seek(Wide::ZERO);
+            Ok(found(ids.nth(n)))
+        }
+        _ => Err(format!("unknown argument {arg:?}")),
+    }
+}
+
+fn main() {
+    let input = scan::read_input("input.txt").expect("Failed to read input.txt");
+
+    let queries: Vec<String> = std::env::args().skip(1).collect();
+    if !queries.is_empty() {
+        let ranges = parse_input(&input).expect("Invalid input");
+        let mut ids = InvalidIds::new(&ranges, false);
+        for arg in &queries {
+            println!("{}", query(&mut ids, arg).expect("Invalid parameters"));
+        }
+        return;
+    }
+    let (part1, part2) = run(&input).expect("Invalid input");
+
+    println!("Part 1: {}", part1);
+    println!("Part 2: {}", part2);
+}
@@ -1,64 +1,176 @@
 // Advent of Code 2025 - Day 03
 // Find maximum joltage from battery banks
 
-use std::fs;
+use common::minimize::{Lines, Units};
+use common::params::Params;
+use common::scan;
+
+pub const SOLVER_VERSION: &str = "1";
+
+pub const DEFAULTS: &[(&str, &str)] = &[("select", "12")];
+
+/// Banks stay as ASCII digits; they compare the same as their values
+fn parse_bank(line: &[u8]) -> Result<&[u8], String> {
+    match line.iter().find(|b| !b.is_ascii_digit()) {
+        Some(_) => Err(format!("non-digit joltage in bank: {:?}", scan::show(line))),
+        None => Ok(line),
+    }
+}
 
 /// Part 1: Pick exactly 2 batteries to form the largest 2-digit number
-fn find_max_joltage_2(bank: &str) -> u32 {
-    let digits: Vec<u8> = bank.bytes().map(|b| b - b'0').collect();
+fn find_max_joltage_2(digits: &[u8]) -> u32 {
     let n = digits.len();
 
     let mut max_joltage = 0;
     for i in 0..n - 1 {
         for j in i + 1..n {
-            let joltage = digits[i] as u32 * 10 + digits[j] as u32;
+            let joltage = (digits[i] - b'0') as u32 * 10 + (digits[j] - b'0') as u32;
             max_joltage = max_joltage.max(joltage);
         }
     }
     max_joltage
 }
 
-/// Part 2: Pick exactly 12 batteries to form the largest 12-digit number
+/// Part 2: Pick exactly `num_to_select` batteries (12 by default) to form the largest number
 /// Uses a greedy approach: at each step, pick the largest digit possible
 /// while ensuring enough digits remain for the rest of the selection
-fn find_max_joltage_12(bank: &str) -> u64 {
-    let digits: Vec<u8> = bank.bytes().map(|b| b - b'0').collect();
+fn find_max_joltage_n(digits: &[u8], num_to_select: usize) -> u64 {
     let n = digits.len();
-    const NUM_TO_SELECT: usize = 12;
 
     let mut result: u64 = 0;
     let mut start_idx = 0;
 
-    for remaining in (1..=NUM_TO_SELECT).rev() {
+    for remaining in (1..=num_to_select).rev() {
         // We can only look up to index (n - remaining) to leave enough for the rest
         let end_idx = n - remaining;
 
         // Find position of maximum digit in valid range (first occurrence on ties)
         let mut max_digit = 0u8;
         let mut max_pos = start_idx;
-        for i in start_idx..=end_idx {
-            if digits[i] > max_digit {
-                max_digit = digits[i];
+        for (i, &digit) in digits.iter().enumerate().take(end_idx + 1).skip(start_idx) {
+            if digit > max_digit {
+                max_digit = digit;
                 max_pos = i;
             }
         }
 
-        result = result * 10 + max_digit as u64;
+        result = result * 10 + (max_digit - b'0') as u64;
         start_idx = max_pos + 1;
     }
 
     result
 }
 
-fn main() {
-    let input = fs::read_to_string("input.txt").expect("Failed to read input.txt");
-    let lines: Vec<&str> = input.lines().filter(|line| !line.is_empty()).collect();
+/// How many batteries part 2 turns on; more than 19 digits overflow a u64
+pub fn selection(params: &Params) -> Result<usize, String> {
+    match params.get("select")? {
+        select @ 1..=19 => Ok(select),
+        select => Err(format!("select must be between 1 and 19, got {select}")),
+    }
+}
 
-    // Part 1
-    let total_part1: u32 = lines.iter().map(|line| find_max_joltage_2(line)).sum();
-    println!("Part 1 - Total output joltage: {}", total_part1);
+/// `bank` if part 2 can pick `num_to_select` batteries from it
+fn long_enough(bank: &[u8], num_to_select: usize) -> Result<&[u8], String> {
+    if bank.len() < num_to_select {
+        return Err(format!(
+            "bank of {} batteries, need at least {num_to_select}",
+            bank.len()
+        ));
+    }
+    Ok(bank)
+}
 
-    // Part 2
-    let total_part2: u64 = lines.iter().map(|line| find_max_joltage_12(line)).sum();
-    println!("Part 2 - Total output joltage: {}", total_part2);
+/// A bank part 2 can pick `num_to_select` batteries from
+fn long_bank(line: &[u8], num_to_select: usize) -> Result<&[u8], String> {
+    long_enough(parse_bank(line)?, num_to_select)
+}
+
+/// Adds one line's bank to both parts' totals, for streaming the input a
+/// line at a time; blank lines are skipped
+pub fn add_bank(totals: &mut (u32, u64), line: &[u8], num_to_select: usize) -> Result<(), String> {
+    if !line.is_empty() {
+        let bank = long_bank(line, num_to_select)?;
+        totals.0 += find_max_joltage_2(bank);
+        totals.1 += find_max_joltage_n(bank, num_to_select);
+    }
+    Ok(())
+}
+
+fn parse_banks(input: &[u8]) -> Result<Vec<&[u8]>, String> {
+    scan::lines(input)
+        .filter(|line| !line.is_empty())
+        .map(parse_bank)
+        .collect()
+}
+
+fn solve_part1(banks: &[&[u8]]) -> u32 {
+    banks.iter().map(|bank| find_max_joltage_2(bank)).sum()
+}
+
+fn solve_part2(banks: &[&[u8]], num_to_select: usize) -> Result<u64, String> {
+    banks
+        .iter()
+        .map(|bank| {
+            long_enough(bank, num_to_select).map(|bank| find_max_joltage_n(bank, num_to_select))
+        })
+        .sum()
+}
+
+pub fn part1(input: &[u8]) -> Result<u32, String> {
+    Ok(solve_part1(&parse_banks(input)?))
+}
+
+pub fn part2(input: &[u8], params: &Params) -> Result<u64, String> {
+    solve_part2(&parse_banks(input)?, selection(params)?)
+}
+
+/// Both answers for an in-memory `input`
+pub fn run(input: &[u8], params: &Params) -> Result<(u32, u64), String> {
+    let banks = parse_banks(input)?;
+    Ok((
+        solve_part1(&banks),
+        solve_part2(&banks, selection(params)?)?,
+    ))
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_banks(input).map(drop)
+}
+
+/// This day's minimizer passes: one line at a time
+pub const UNITS: &[&dyn Units] = &[&Lines];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+
+    #[test]
+    fn test_select() {
+        let params = |select: &str| {
+            let args = [format!("--param=select={select}")].into_iter();
+            Params::from_sources("day03", DEFAULTS, None, args).unwrap()
+        };
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(
+            run(b"811111111111119\n", &params("12")),
+            Ok((89, 811111111119))
+        );
+        assert!(run(input, &params("0")).is_err());
+        assert!(run(input, &params("20")).is_err());
+        // Larger than a bank, rather than underflowing in the greedy pick
+        assert_eq!(
+            run(b"12345\n", &params("6")),
+            Err("bank of 5 batteries, need at least 6".to_string())
+        );
+    }
+
+    #[test]
+    fn fuzz_parse_bank() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(|input| scan::lines(input).try_for_each(|line| parse_bank(line).map(drop)));
+    }
 }
@@ -0,0 +1,19 @@
+use common::params::Params;
+use common::scan;
+use day03::{add_bank, selection, DEFAULTS};
+
+fn main() {
+    let params = Params::load("day03", DEFAULTS).expect("Invalid parameters");
+    let num_to_select = selection(&params).expect("Invalid parameters");
+
+    // Banks are independent, so stream them instead of holding the whole input
+    let mut totals = (0, 0);
+    scan::for_each_line("input.txt", |line| {
+        add_bank(&mut totals, line, num_to_select)
+    })
+    .expect("Failed to read input.txt")
+    .expect("Invalid input");
+
+    println!("Part 1 - Total output joltage: {}", totals.0);
+    println!("Part 2 - Total output joltage: {}", totals.1);
+}
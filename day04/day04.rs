@@ -1,4 +1,12 @@
-use std::fs;
+use common::fuzz::Rng;
+use common::minimize::{Lines, Units};
+use common::params::Params;
+use common::scan::{self, Grid};
+use common::svg::Svg;
+
+pub const SOLVER_VERSION: &str = "1";
+
+pub const DEFAULTS: &[(&str, &str)] = &[("threshold", "4")];
 
 const DIRECTIONS: [(isize, isize); 8] = [
     (-1, 0),  // N
@@ -11,60 +19,49 @@ const DIRECTIONS: [(isize, isize); 8] = [
     (-1, -1), // NW
 ];
 
-fn parse_grid(input: &str) -> Vec<Vec<char>> {
-    input
-        .trim()
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect()
-}
+fn parse_grid(input: &[u8]) -> Result<Grid<&[u8]>, String> {
+    let grid = Grid::parse(input.trim_ascii())?;
 
-fn count_adjacent_rolls(grid: &[Vec<char>], row: usize, col: usize) -> usize {
-    let rows = grid.len();
-    let cols = grid[0].len();
+    if let Some((_, _, cell)) = grid.cells().find(|&(_, _, b)| b != b'@' && b != b'.') {
+        return Err(format!("unexpected cell {:?}", scan::show(&[cell])));
+    }
+
+    Ok(grid)
+}
 
+fn count_adjacent_rolls<B: AsRef<[u8]>>(grid: &Grid<B>, row: usize, col: usize) -> usize {
     DIRECTIONS
         .iter()
-        .filter(|&(dr, dc)| {
-            let Some(new_row) = row.checked_add_signed(*dr) else {
-                return false;
-            };
-            let Some(new_col) = col.checked_add_signed(*dc) else {
-                return false;
-            };
-
-            new_row < rows && new_col < cols && grid[new_row][new_col] == '@'
-        })
+        .filter(|&&(dr, dc)| grid.get_offset(row, col, dr, dc) == Some(b'@'))
         .count()
 }
 
-fn find_accessible_rolls(grid: &[Vec<char>]) -> Vec<(usize, usize)> {
-    let rows = grid.len();
-    let cols = grid[0].len();
-
-    (0..rows)
-        .flat_map(|row| (0..cols).map(move |col| (row, col)))
-        .filter(|&(row, col)| grid[row][col] == '@' && count_adjacent_rolls(grid, row, col) < 4)
+fn find_accessible_rolls<B: AsRef<[u8]>>(grid: &Grid<B>, threshold: usize) -> Vec<(usize, usize)> {
+    grid.cells()
+        .filter(|&(row, col, cell)| {
+            cell == b'@' && count_adjacent_rolls(grid, row, col) < threshold
+        })
+        .map(|(row, col, _)| (row, col))
         .collect()
 }
 
-fn solve_part1(grid: &[Vec<char>]) -> usize {
-    find_accessible_rolls(grid).len()
+fn solve_part1(grid: &Grid<&[u8]>, threshold: usize) -> usize {
+    find_accessible_rolls(grid, threshold).len()
 }
 
-fn solve_part2(grid: &[Vec<char>]) -> usize {
-    let mut grid = grid.to_vec();
+fn solve_part2(grid: &Grid<&[u8]>, threshold: usize) -> usize {
+    let mut grid = grid.to_owned();
     let mut total_removed = 0;
 
     loop {
-        let accessible = find_accessible_rolls(&grid);
+        let accessible = find_accessible_rolls(&grid, threshold);
 
         if accessible.is_empty() {
             break;
         }
 
         for (row, col) in accessible.iter() {
-            grid[*row][*col] = '.';
+            grid.set(*row, *col, b'.');
         }
 
         total_removed += accessible.len();
@@ -73,10 +70,105 @@ fn solve_part2(grid: &[Vec<char>]) -> usize {
     total_removed
 }
 
-fn main() {
-    let input = fs::read_to_string("input.txt").expect("Failed to read input.txt");
-    let grid = parse_grid(&input);
+pub fn part1(input: &[u8], params: &Params) -> Result<usize, String> {
+    Ok(solve_part1(&parse_grid(input)?, params.get("threshold")?))
+}
+
+pub fn part2(input: &[u8], params: &Params) -> Result<usize, String> {
+    Ok(solve_part2(&parse_grid(input)?, params.get("threshold")?))
+}
+
+/// Both answers for `input`
+pub fn run(input: &[u8], params: &Params) -> Result<(usize, usize), String> {
+    let grid = parse_grid(input)?;
+    let threshold = params.get("threshold")?;
+    Ok((solve_part1(&grid, threshold), solve_part2(&grid, threshold)))
+}
+
+/// A random square grid of about `size` cells, three in five of them rolls
+pub fn generate(size: usize, seed: u64) -> Vec<u8> {
+    let side = size.isqrt().max(1);
+    let mut rng = Rng::new(seed);
+    let mut grid = Vec::with_capacity((side + 1) * side);
+    for _ in 0..side {
+        grid.extend((0..side).map(|_| if rng.below(5) < 3 { b'@' } else { b'.' }));
+        grid.push(b'\n');
+    }
+    grid
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_grid(input).map(drop)
+}
+
+/// The grid as SVG: rolls part 1 finds accessible in orange, the rest in
+/// slate
+pub fn svg(input: &[u8], params: &Params) -> Result<String, String> {
+    let grid = parse_grid(input)?;
+    let accessible = find_accessible_rolls(&grid, params.get("threshold")?);
+    let mut reachable = vec![false; grid.width() * grid.height()];
+    for &(row, col) in &accessible {
+        reachable[row * grid.width() + col] = true;
+    }
+
+    let rolls = grid.cells().filter(|&(_, _, cell)| cell == b'@').count();
+    let mut svg = Svg::new(grid.width() as f64, grid.height() as f64);
+    svg.title(&format!("{rolls} rolls, {} accessible", accessible.len()));
+    for (row, col, _) in grid.cells().filter(|&(_, _, cell)| cell == b'@') {
+        let fill = match reachable[row * grid.width() + col] {
+            true => "#e8a33d",
+            false => "#5b6b7a",
+        };
+        svg.rect(col as f64, row as f64, 1.0, 1.0, fill);
+    }
+    Ok(svg.finish())
+}
+
+/// This day's minimizer passes: one line at a time
+pub const UNITS: &[&dyn Units] = &[&Lines];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+    use common::snapshot::assert_snapshot;
+
+    #[test]
+    fn test_svg() {
+        let params = Params::from_sources("day04", DEFAULTS, None, Vec::new()).unwrap();
+        let svg = svg(include_bytes!("test_input.txt"), &params).unwrap();
+        assert!(svg.contains("<title>71 rolls, 13 accessible</title>"));
+        assert_eq!(svg.matches("#e8a33d").count(), 13);
+        assert_eq!(svg.matches("<rect").count(), 71);
+        assert_snapshot(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/test_input.svg"),
+            &svg,
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(100, 1);
+        let grid = parse_grid(&input).unwrap();
+        assert_eq!((grid.width(), grid.height()), (10, 10));
+        assert_eq!(generate(100, 1), input);
+        assert_ne!(generate(100, 2), input);
+    }
+
+    #[test]
+    fn test_example() {
+        let params = Params::from_sources("day04", DEFAULTS, None, Vec::new()).unwrap();
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(part1(input, &params), Ok(13));
+        assert_eq!(part2(input, &params), Ok(43));
+    }
 
-    println!("Part 1: {}", solve_part1(&grid));
-    println!("Part 2: {}", solve_part2(&grid));
+    #[test]
+    fn fuzz_parse_grid() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(|input| parse_grid(input).map(|grid| grid.height()));
+    }
 }
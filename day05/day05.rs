@@ -1,19 +1,38 @@
-use std::fs;
+use algo::ranges::merge_ranges;
+use common::minimize::{non_empty_lines, Units};
+use common::scan;
 
-fn parse_input(input: &str) -> (Vec<(i64, i64)>, Vec<i64>) {
-    let parts: Vec<&str> = input.trim().split("\n\n").collect();
+pub const SOLVER_VERSION: &str = "1";
 
-    let ranges = parts[0]
-        .lines()
+type Inventory = (Vec<(i64, i64)>, Vec<i64>);
+
+fn parse_input(input: &[u8]) -> Result<Inventory, String> {
+    let lines: Vec<&[u8]> = scan::lines(input.trim_ascii()).collect();
+    let blank = lines
+        .iter()
+        .position(|line| line.is_empty())
+        .ok_or("expected ranges and ingredients separated by a blank line")?;
+
+    let ranges = lines[..blank]
+        .iter()
         .map(|line| {
-            let (start, stop) = line.split_once('-').unwrap();
-            (start.parse().unwrap(), stop.parse().unwrap())
+            let invalid = || format!("expected start-stop: {:?}", scan::show(line));
+            let (start, stop) = scan::split_once(line, b'-').ok_or_else(invalid)?;
+            Ok((
+                scan::parse(start).ok_or_else(invalid)?,
+                scan::parse(stop).ok_or_else(invalid)?,
+            ))
         })
-        .collect();
+        .collect::<Result<_, String>>()?;
 
-    let ingredients = parts[1].lines().map(|line| line.parse().unwrap()).collect();
+    let ingredients = lines[blank + 1..]
+        .iter()
+        .map(|line| {
+            scan::parse(line).ok_or_else(|| format!("invalid ingredient: {:?}", scan::show(line)))
+        })
+        .collect::<Result<_, String>>()?;
 
-    (ranges, ingredients)
+    Ok((ranges, ingredients))
 }
 
 fn is_fresh(ranges: &[(i64, i64)], id: i64) -> bool {
@@ -29,33 +48,6 @@ fn solve_part1(ranges: &[(i64, i64)], ingredients: &[i64]) -> usize {
         .count()
 }
 
-fn merge_ranges(ranges: &[(i64, i64)]) -> Vec<(i64, i64)> {
-    if ranges.is_empty() {
-        return vec![];
-    }
-
-    let mut sorted: Vec<_> = ranges.to_vec();
-    sorted.sort_by_key(|(start, _)| *start);
-
-    let mut merged: Vec<(i64, i64)> = vec![];
-
-    for (start, stop) in sorted {
-        if let Some((_, prev_stop)) = merged.last_mut() {
-            if start <= *prev_stop + 1 {
-                // Overlapping or adjacent, merge them
-                *prev_stop = (*prev_stop).max(stop);
-            } else {
-                // No overlap, add new range
-                merged.push((start, stop));
-            }
-        } else {
-            merged.push((start, stop));
-        }
-    }
-
-    merged
-}
-
 fn solve_part2(ranges: &[(i64, i64)]) -> i64 {
     merge_ranges(ranges)
         .iter()
@@ -63,10 +55,103 @@ fn solve_part2(ranges: &[(i64, i64)]) -> i64 {
         .sum()
 }
 
-fn main() {
-    let input = fs::read_to_string("input.txt").expect("Failed to read input.txt");
-    let (ranges, ingredients) = parse_input(&input);
+pub fn part1(input: &[u8]) -> Result<usize, String> {
+    let (ranges, ingredients) = parse_input(input)?;
+    Ok(solve_part1(&ranges, &ingredients))
+}
+
+pub fn part2(input: &[u8]) -> Result<i64, String> {
+    let (ranges, _) = parse_input(input)?;
+    Ok(solve_part2(&ranges))
+}
+
+/// Both answers for `input`
+pub fn run(input: &[u8]) -> Result<(usize, i64), String> {
+    let (ranges, ingredients) = parse_input(input)?;
+    Ok((solve_part1(&ranges, &ingredients), solve_part2(&ranges)))
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_input(input).map(drop)
+}
+
+/// Minimizer units: single ranges and ingredients, keeping the blank
+/// line between the two sections
+pub struct Entries;
+
+fn sections(input: &[u8]) -> (Vec<&[u8]>, Vec<&[u8]>) {
+    let (ranges, ingredients) = match input.windows(2).position(|w| w == b"\n\n") {
+        Some(at) => (&input[..at], &input[at + 2..]),
+        None => (input, &[][..]),
+    };
+    (
+        non_empty_lines(ranges).collect(),
+        non_empty_lines(ingredients).collect(),
+    )
+}
+
+impl Units for Entries {
+    fn count(&self, input: &[u8]) -> usize {
+        let (ranges, ingredients) = sections(input);
+        ranges.len() + ingredients.len()
+    }
+
+    fn keep(&self, input: &[u8], kept: &[usize]) -> Vec<u8> {
+        let (ranges, ingredients) = sections(input);
+        let pick = |offset: usize, lines: &[&[u8]]| -> Vec<u8> {
+            kept.iter()
+                .filter_map(|&i| lines.get(i.checked_sub(offset)?))
+                .flat_map(|line| [*line, b"\n"])
+                .flatten()
+                .copied()
+                .collect()
+        };
+        [pick(0, &ranges), pick(ranges.len(), &ingredients)].join(&b'\n')
+    }
+}
+
+/// This day's minimizer passes
+pub const UNITS: &[&dyn Units] = &[&Entries];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+    use common::minimize::{disagree, minimize};
+
+    #[test]
+    fn test_example() {
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(part1(input), Ok(3));
+        assert_eq!(part2(input), Ok(14));
+    }
 
-    println!("Part 1: {}", solve_part1(&ranges, &ingredients));
-    println!("Part 2: {}", solve_part2(&ranges));
+    #[test]
+    fn test_shrink_entries() {
+        // Fails while range 12-18 and ingredient 11 are both present
+        let fails = |s: &[u8]| {
+            let (ranges, ingredients) = sections(s);
+            ranges.contains(&&b"12-18"[..]) && ingredients.contains(&&b"11"[..])
+        };
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(minimize(input, UNITS, fails), b"12-18\n\n11\n");
+
+        // Summing the ranges without merging them disagrees with part 2
+        // exactly when two ranges overlap; one ingredient stays, as without
+        // any both sides reject the input the same way
+        let unmerged = |s: &[u8]| {
+            parse_input(s).map(|(ranges, _)| ranges.iter().map(|(a, b)| b - a + 1).sum::<i64>())
+        };
+        let fails = disagree(part2, unmerged);
+        assert_eq!(minimize(input, UNITS, fails), b"16-20\n12-18\n\n1\n");
+    }
+
+    #[test]
+    fn fuzz_parse_input() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(parse_input);
+    }
 }
@@ -0,0 +1,10 @@
+use common::scan;
+use day05::run;
+
+fn main() {
+    let input = scan::read_input("input.txt").expect("Failed to read input.txt");
+    let (part1, part2) = run(&input).expect("Invalid input");
+
+    println!("Part 1: {}", part1);
+    println!("Part 2: {}", part2);
+}
@@ -1,53 +1,30 @@
-use std::fs;
+use common::minimize::{Lines, Units};
+use common::scan;
 
-fn main() {
-    let input = fs::read_to_string("input.txt").expect("Failed to read input.txt");
-    let lines: Vec<&str> = input.lines().collect();
-    let h = lines.len();
-    let w = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+pub const SOLVER_VERSION: &str = "1";
 
-    // Pad lines to same width
-    let grid: Vec<Vec<char>> = lines
-        .iter()
-        .map(|l| {
-            let mut row: Vec<char> = l.chars().collect();
-            row.resize(w, ' ');
-            row
-        })
-        .collect();
-
-    // Get a column as chars
-    let col = |c: usize| -> Vec<char> { (0..h).map(|r| grid[r][c]).collect() };
-
-    // Check if column is all spaces (separator)
-    let is_sep = |c: usize| col(c).iter().all(|&ch| ch == ' ');
-
-    // Find problem column groups
-    let mut problems: Vec<Vec<usize>> = Vec::new();
-    let mut current: Vec<usize> = Vec::new();
+/// Rows are kept as slices of the input; cells past the end of a short row
+/// read as spaces, so no padded copy is needed
+struct Worksheet<'a> {
+    rows: Vec<&'a [u8]>,
+    problems: Vec<Vec<usize>>,
+}
 
-    for c in 0..w {
-        if is_sep(c) {
-            if !current.is_empty() {
-                problems.push(current.clone());
-                current.clear();
-            }
-        } else {
-            current.push(c);
-        }
-    }
-    if !current.is_empty() {
-        problems.push(current);
+impl Worksheet<'_> {
+    fn cell(&self, row: usize, col: usize) -> u8 {
+        self.rows[row].get(col).copied().unwrap_or(b' ')
     }
 
-    // Solve a problem
-    let solve = |cols: &[usize], vertical: bool| -> i64 {
+    /// Solve one problem, reading its numbers by row or, if `vertical`, by column
+    fn solve(&self, cols: &[usize], vertical: bool) -> i64 {
+        let h = self.rows.len();
+
         // Find operator
         let op: fn(i64, i64) -> i64 = cols
             .iter()
-            .find_map(|&c| match grid[h - 1][c] {
-                '+' => Some((|a, b| a + b) as fn(i64, i64) -> i64),
-                '*' => Some((|a, b| a * b) as fn(i64, i64) -> i64),
+            .find_map(|&c| match self.cell(h - 1, c) {
+                b'+' => Some((|a, b| a + b) as fn(i64, i64) -> i64),
+                b'*' => Some((|a, b| a * b) as fn(i64, i64) -> i64),
                 _ => None,
             })
             .expect("No operator found");
@@ -56,38 +33,135 @@ fn main() {
             // Each column is a number read top-to-bottom
             cols.iter()
                 .filter_map(|&c| {
-                    let s: String = (0..h - 1)
-                        .map(|r| grid[r][c])
-                        .filter(|ch| ch.is_ascii_digit())
-                        .collect();
-                    if s.is_empty() {
-                        None
-                    } else {
-                        s.parse().ok()
-                    }
+                    let mut digits = (0..h - 1)
+                        .map(|r| self.cell(r, c))
+                        .filter(|b| b.is_ascii_digit())
+                        .peekable();
+                    digits.peek()?;
+                    digits.try_fold(0i64, |n, b| {
+                        n.checked_mul(10)?.checked_add((b - b'0') as i64)
+                    })
                 })
                 .collect()
         } else {
             // Each row is a number read left-to-right
+            let (first, last) = (cols[0], cols[cols.len() - 1]);
             (0..h - 1)
                 .filter_map(|r| {
-                    let s: String = cols.iter().map(|&c| grid[r][c]).collect::<String>();
-                    let trimmed = s.trim();
+                    let row = self.rows[r];
+                    let s = &row[first.min(row.len())..(last + 1).min(row.len())];
+                    let trimmed = s.trim_ascii();
                     if trimmed.is_empty() {
                         None
                     } else {
-                        trimmed.parse().ok()
+                        scan::parse(trimmed)
                     }
                 })
                 .collect()
         };
 
         nums.into_iter().reduce(op).unwrap_or(0)
+    }
+}
+
+fn parse_worksheet(input: &[u8]) -> Result<Worksheet<'_>, String> {
+    let rows: Vec<&[u8]> = scan::lines(input).collect();
+    let h = rows.len();
+    if h < 2 {
+        return Err("expected number rows followed by an operator row".to_string());
+    }
+    let w = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let mut sheet = Worksheet {
+        rows,
+        problems: Vec::new(),
     };
 
-    let part1: i64 = problems.iter().map(|p| solve(p, false)).sum();
-    let part2: i64 = problems.iter().rev().map(|p| solve(p, true)).sum();
+    // Check if column is all spaces (separator)
+    let is_sep = |c: usize| (0..h).all(|r| sheet.cell(r, c) == b' ');
 
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+    // Find problem column groups
+    let mut problems: Vec<Vec<usize>> = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+
+    for c in 0..w {
+        if is_sep(c) {
+            if !current.is_empty() {
+                problems.push(current.clone());
+                current.clear();
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        problems.push(current);
+    }
+
+    // Every problem needs its operator on the last row
+    let has_operator = |cols: &Vec<usize>| {
+        cols.iter()
+            .any(|&c| matches!(sheet.cell(h - 1, c), b'+' | b'*'))
+    };
+    if let Some(cols) = problems.iter().find(|cols| !has_operator(cols)) {
+        return Err(format!("no operator below column {}", cols[0] + 1));
+    }
+
+    sheet.problems = problems;
+    Ok(sheet)
+}
+
+fn solve_part1(sheet: &Worksheet<'_>) -> i64 {
+    sheet.problems.iter().map(|p| sheet.solve(p, false)).sum()
+}
+
+fn solve_part2(sheet: &Worksheet<'_>) -> i64 {
+    sheet
+        .problems
+        .iter()
+        .rev()
+        .map(|p| sheet.solve(p, true))
+        .sum()
+}
+
+pub fn part1(input: &[u8]) -> Result<i64, String> {
+    Ok(solve_part1(&parse_worksheet(input)?))
+}
+
+pub fn part2(input: &[u8]) -> Result<i64, String> {
+    Ok(solve_part2(&parse_worksheet(input)?))
+}
+
+/// Both answers for `input`
+pub fn run(input: &[u8]) -> Result<(i64, i64), String> {
+    let sheet = parse_worksheet(input)?;
+    Ok((solve_part1(&sheet), solve_part2(&sheet)))
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_worksheet(input).map(drop)
+}
+
+/// This day's minimizer passes: one line at a time
+pub const UNITS: &[&dyn Units] = &[&Lines];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+
+    #[test]
+    fn test_example() {
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(part1(input), Ok(65581894710));
+        assert_eq!(part2(input), Ok(231156135405));
+    }
+
+    #[test]
+    fn fuzz_parse_worksheet() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(|input| parse_worksheet(input).map(|sheet| sheet.problems.len()));
+    }
 }
@@ -2,20 +2,49 @@
 // Tachyon beam splitting in the manifold
 
 use std::collections::{HashMap, HashSet};
-use std::fs;
+use std::hash::BuildHasher;
+
+use common::hash::FxBuildHasher;
+use common::minimize::{Lines, Units};
+use common::scan::Grid;
+use common::svg::Svg;
+
+pub const SOLVER_VERSION: &str = "1";
+
+/// Checks the manifold is a rectangle with the start on the first row and
+/// no splitter on the outer columns, so beams never leave the grid
+fn parse_input(input: &[u8]) -> Result<Grid<&[u8]>, String> {
+    let grid = Grid::parse(input.trim_ascii())?;
+    if start_col(&grid).is_none() {
+        return Err("no start position S on the first row".to_string());
+    }
+
+    let width = grid.width();
+    for row in 0..grid.height() {
+        if grid.get(row, 0) == b'^' || grid.get(row, width - 1) == b'^' {
+            return Err(format!("splitter on the edge of row {}", row + 1));
+        }
+    }
+
+    Ok(grid)
+}
+
+fn start_col(grid: &Grid<&[u8]>) -> Option<usize> {
+    grid.row(0).iter().position(|&b| b == b'S')
+}
 
 /// Part 1: Count beam splits (beams merge at same position)
-fn part1(lines: &[&str]) -> u32 {
-    let height = lines.len();
-    let start_col = lines[0].find('S').unwrap();
+fn solve_part1<S: BuildHasher + Default>(grid: &Grid<&[u8]>) -> u32 {
+    let height = grid.height();
+    let start_col = start_col(grid).unwrap();
 
-    let mut beams: HashSet<usize> = HashSet::new();
+    let mut beams: HashSet<usize, S> = HashSet::default();
     beams.insert(start_col);
     let mut splits = 0;
 
     for row in 0..height - 1 {
-        let next_row = lines[row + 1].as_bytes();
-        let mut new_beams: HashSet<usize> = HashSet::new();
+        let next_row = grid.row(row + 1);
+        let mut new_beams: HashSet<usize, S> = HashSet::default();
 
         for &col in &beams {
             if next_row[col] == b'^' {
@@ -33,18 +62,18 @@ fn part1(lines: &[&str]) -> u32 {
 }
 
 /// Part 2: Count timelines (many-worlds interpretation with memoization)
-fn part2(lines: &[&str]) -> i64 {
-    let height = lines.len();
-    let start_col = lines[0].find('S').unwrap();
+fn solve_part2<S: BuildHasher + Default>(grid: &Grid<&[u8]>) -> i64 {
+    let height = grid.height();
+    let start_col = start_col(grid).unwrap();
 
-    let mut memo: HashMap<(usize, usize), i64> = HashMap::new();
+    let mut memo: HashMap<(usize, usize), i64, S> = HashMap::default();
 
-    fn timelines(
+    fn timelines<S: BuildHasher>(
         row: usize,
         col: usize,
-        lines: &[&str],
+        grid: &Grid<&[u8]>,
         height: usize,
-        memo: &mut HashMap<(usize, usize), i64>,
+        memo: &mut HashMap<(usize, usize), i64, S>,
     ) -> i64 {
         if row == height - 1 {
             return 1;
@@ -55,24 +84,132 @@ fn part2(lines: &[&str]) -> i64 {
             return val;
         }
 
-        let result = if lines[row + 1].as_bytes()[col] == b'^' {
-            timelines(row + 1, col - 1, lines, height, memo)
-                + timelines(row + 1, col + 1, lines, height, memo)
+        let result = if grid.get(row + 1, col) == b'^' {
+            timelines(row + 1, col - 1, grid, height, memo)
+                + timelines(row + 1, col + 1, grid, height, memo)
         } else {
-            timelines(row + 1, col, lines, height, memo)
+            timelines(row + 1, col, grid, height, memo)
         };
 
         memo.insert(key, result);
         result
     }
 
-    timelines(0, start_col, lines, height, &mut memo)
+    timelines(0, start_col, grid, height, &mut memo)
+}
+
+pub fn part1(input: &[u8]) -> Result<u32, String> {
+    part1_with_hasher::<FxBuildHasher>(input)
+}
+
+pub fn part2(input: &[u8]) -> Result<i64, String> {
+    part2_with_hasher::<FxBuildHasher>(input)
+}
+
+/// `part1` with the beam sets hashed by `S`, for the hash benchmark
+pub fn part1_with_hasher<S: BuildHasher + Default>(input: &[u8]) -> Result<u32, String> {
+    Ok(solve_part1::<S>(&parse_input(input)?))
+}
+
+/// `part2` with the timeline memo hashed by `S`, for the hash benchmark
+pub fn part2_with_hasher<S: BuildHasher + Default>(input: &[u8]) -> Result<i64, String> {
+    Ok(solve_part2::<S>(&parse_input(input)?))
+}
+
+/// Both answers for `input`
+pub fn run(input: &[u8]) -> Result<(u32, i64), String> {
+    let grid = parse_input(input)?;
+    Ok((
+        solve_part1::<FxBuildHasher>(&grid),
+        solve_part2::<FxBuildHasher>(&grid),
+    ))
 }
 
-fn main() {
-    let input = fs::read_to_string("input.txt").expect("Failed to read input.txt");
-    let lines: Vec<&str> = input.lines().filter(|line| !line.is_empty()).collect();
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_input(input).map(drop)
+}
+
+/// The manifold as SVG: beams in blue, splitters a beam reaches in red and
+/// the others in grey, the start in green
+pub fn svg(input: &[u8]) -> Result<String, String> {
+    let grid = parse_input(input)?;
+    let (width, height) = (grid.width(), grid.height());
+    let mut svg = Svg::new(width as f64, height as f64);
+    let mut cell = |row: usize, col: usize, fill: &str| {
+        svg.rect(col as f64, row as f64, 1.0, 1.0, fill);
+    };
 
-    println!("Part 1: {}", part1(&lines));
-    println!("Part 2: {}", part2(&lines));
+    // Per column of the current row: a beam, or a splitter a beam hit
+    let mut beams = vec![false; width];
+    let mut hit = vec![false; width];
+    beams[start_col(&grid).unwrap()] = true;
+    let mut splits = 0;
+    for row in 0..height {
+        for col in 0..width {
+            match grid.get(row, col) {
+                b'S' => cell(row, col, "#27ae60"),
+                b'^' if hit[col] => cell(row, col, "#c0392b"),
+                b'^' => cell(row, col, "#95a5a6"),
+                _ if beams[col] => cell(row, col, "#5dade2"),
+                _ => {}
+            }
+        }
+        if row + 1 == height {
+            break;
+        }
+
+        let mut next = vec![false; width];
+        hit.fill(false);
+        for col in (0..width).filter(|&col| beams[col]) {
+            if grid.get(row + 1, col) == b'^' {
+                splits += 1;
+                hit[col] = true;
+                next[col - 1] = true;
+                next[col + 1] = true;
+            } else {
+                next[col] = true;
+            }
+        }
+        beams = next;
+    }
+    svg.title(&format!("{splits} splits"));
+    Ok(svg.finish())
+}
+
+/// This day's minimizer passes: one line at a time
+pub const UNITS: &[&dyn Units] = &[&Lines];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+    use common::snapshot::assert_snapshot;
+
+    #[test]
+    fn test_example() {
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(part1(input), Ok(13));
+        assert_eq!(part2(input), Ok(18));
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = svg(include_bytes!("test_input.txt")).unwrap();
+        assert!(svg.contains("<title>13 splits</title>"));
+        assert_eq!(svg.matches("#c0392b").count(), 13);
+        assert_eq!(svg.matches("#27ae60").count(), 1);
+        assert_snapshot(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/test_input.svg"),
+            &svg,
+        );
+    }
+
+    #[test]
+    fn fuzz_parse_input() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(|input| parse_input(input).map(|grid| grid.height()));
+    }
 }
@@ -1,83 +1,55 @@
 // Advent of Code 2025 - Day 08
 // Junction box circuits with Union-Find
 
-use std::fs;
+use std::fmt::Write;
 
-struct UnionFind {
-    parent: Vec<usize>,
-    rank: Vec<usize>,
-    components: usize,
-}
-
-impl UnionFind {
-    fn new(n: usize) -> Self {
-        Self {
-            parent: (0..n).collect(),
-            rank: vec![0; n],
-            components: n,
-        }
-    }
-
-    fn find(&mut self, x: usize) -> usize {
-        if self.parent[x] != x {
-            self.parent[x] = self.find(self.parent[x]); // Path compression
-        }
-        self.parent[x]
-    }
+use algo::union_find::UnionFind;
+use common::fuzz::Rng;
+use common::minimize::{Lines, Units};
+use common::params::Params;
+use common::scan::{self, Scanner};
 
-    fn union(&mut self, x: usize, y: usize) -> bool {
-        let px = self.find(x);
-        let py = self.find(y);
+pub const SOLVER_VERSION: &str = "2";
 
-        if px == py {
-            return false;
-        }
-
-        // Union by rank
-        match self.rank[px].cmp(&self.rank[py]) {
-            std::cmp::Ordering::Less => self.parent[px] = py,
-            std::cmp::Ordering::Greater => self.parent[py] = px,
-            std::cmp::Ordering::Equal => {
-                self.parent[py] = px;
-                self.rank[px] += 1;
-            }
-        }
-        self.components -= 1;
-        true
-    }
+pub const DEFAULTS: &[(&str, &str)] = &[("pairs", "1000"), ("top", "3")];
 
-    fn circuit_sizes(&mut self) -> Vec<usize> {
-        let mut counts = std::collections::HashMap::new();
-        for i in 0..self.parent.len() {
-            let root = self.find(i);
-            *counts.entry(root).or_insert(0) += 1;
-        }
-        let mut sizes: Vec<_> = counts.values().copied().collect();
-        sizes.sort_by(|a, b| b.cmp(a));
-        sizes
-    }
-}
+/// Coordinates are kept small enough that squared distances and the part 2
+/// product fit in an i64
+const MAX_COORD: i64 = 1 << 29;
 
 fn dist_sq(a: (i64, i64, i64), b: (i64, i64, i64)) -> i64 {
     let (dx, dy, dz) = (b.0 - a.0, b.1 - a.1, b.2 - a.2);
     dx * dx + dy * dy + dz * dz
 }
 
-fn main() {
-    let input = fs::read_to_string("input.txt").expect("Failed to read input.txt");
+fn parse_box(line: &[u8]) -> Option<(i64, i64, i64)> {
+    let mut scanner = Scanner::new(line);
+    let x = scanner.int()?;
+    scanner.expect(b',')?;
+    let y = scanner.int()?;
+    scanner.expect(b',')?;
+    let z = scanner.int()?;
+    let in_range = |v: i64| (-MAX_COORD..=MAX_COORD).contains(&v);
+    (scanner.is_empty() && [x, y, z].into_iter().all(in_range)).then_some((x, y, z))
+}
 
-    let boxes: Vec<(i64, i64, i64)> = input
-        .lines()
+fn parse_input(input: &[u8]) -> Result<Vec<(i64, i64, i64)>, String> {
+    let boxes: Vec<(i64, i64, i64)> = scan::lines(input)
         .filter(|line| !line.is_empty())
         .map(|line| {
-            let parts: Vec<i64> = line.split(',').map(|s| s.parse().unwrap()).collect();
-            (parts[0], parts[1], parts[2])
+            parse_box(line).ok_or_else(|| format!("expected x,y,z: {:?}", scan::show(line)))
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    let n = boxes.len();
+    if boxes.len() < 2 {
+        return Err("need at least two junction boxes".to_string());
+    }
+    Ok(boxes)
+}
 
-    // Generate all pairs sorted by distance
+/// All pairs of boxes, shortest first
+fn sorted_pairs(boxes: &[(i64, i64, i64)]) -> Vec<(i64, usize, usize)> {
+    let n = boxes.len();
     let mut pairs: Vec<(i64, usize, usize)> = Vec::new();
     for i in 0..n - 1 {
         for j in i + 1..n {
@@ -85,29 +57,155 @@ fn main() {
         }
     }
     pairs.sort_by_key(|p| p.0);
+    pairs
+}
 
-    // Part 1: Connect 1000 shortest pairs
-    let mut uf1 = UnionFind::new(n);
-    for (_, i, j) in pairs.iter().take(1000) {
-        uf1.union(*i, *j);
+/// Connect the `num_pairs` shortest pairs, multiply the `top` largest circuits
+fn solve_part1(
+    boxes: &[(i64, i64, i64)],
+    pairs: &[(i64, usize, usize)],
+    num_pairs: usize,
+    top: usize,
+) -> Result<i64, String> {
+    let mut uf = UnionFind::new(boxes.len());
+    for (_, i, j) in pairs.iter().take(num_pairs) {
+        uf.union(*i, *j);
     }
 
-    let sizes = uf1.circuit_sizes();
-    let part1 = sizes[0] as i64 * sizes[1] as i64 * sizes[2] as i64;
+    let sizes = uf.set_sizes();
+    sizes
+        .iter()
+        .take(top)
+        .try_fold(1i64, |product, &size| product.checked_mul(size as i64))
+        .ok_or_else(|| format!("product of the {top} largest circuits overflows i64"))
+}
 
-    // Part 2: Find last connection that unifies all circuits
-    let mut uf2 = UnionFind::new(n);
+/// Find last connection that unifies all circuits
+fn solve_part2(boxes: &[(i64, i64, i64)], pairs: &[(i64, usize, usize)]) -> i64 {
+    let mut uf = UnionFind::new(boxes.len());
     let mut last_pair = (0, 0);
 
-    for (_, i, j) in &pairs {
-        if uf2.union(*i, *j) && uf2.components == 1 {
-            last_pair = (*i, *j);
+    for &(_, i, j) in pairs {
+        if uf.union(i, j) && uf.components() == 1 {
+            last_pair = (i, j);
             break;
         }
     }
 
-    let part2 = boxes[last_pair.0].0 * boxes[last_pair.1].0;
+    boxes[last_pair.0].0 * boxes[last_pair.1].0
+}
+
+/// Connect the shortest pairs (1000 by default), multiply the largest circuits
+pub fn part1(input: &[u8], params: &Params) -> Result<i64, String> {
+    let (num_pairs, top) = (params.get("pairs")?, params.get("top")?);
+    let boxes = parse_input(input)?;
+    solve_part1(&boxes, &sorted_pairs(&boxes), num_pairs, top)
+}
+
+/// Find last connection that unifies all circuits
+pub fn part2(input: &[u8]) -> Result<i64, String> {
+    let boxes = parse_input(input)?;
+    Ok(solve_part2(&boxes, &sorted_pairs(&boxes)))
+}
+
+/// Both answers for `input`, sorting the pairs once for both parts
+pub fn run(input: &[u8], params: &Params) -> Result<(i64, i64), String> {
+    let (num_pairs, top) = (params.get("pairs")?, params.get("top")?);
+    let boxes = parse_input(input)?;
+    let pairs = sorted_pairs(&boxes);
+    Ok((
+        solve_part1(&boxes, &pairs, num_pairs, top)?,
+        solve_part2(&boxes, &pairs),
+    ))
+}
+
+/// `size` junction boxes (at least two) at random coordinates in the range
+/// the real inputs use
+pub fn generate(size: usize, seed: u64) -> Vec<u8> {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    for _ in 0..size.max(2) {
+        let (x, y, z) = (rng.below(100_000), rng.below(100_000), rng.below(100_000));
+        let _ = writeln!(input, "{x},{y},{z}");
+    }
+    input.into_bytes()
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_input(input).map(drop)
+}
+
+/// This day's minimizer passes: one line at a time
+pub const UNITS: &[&dyn Units] = &[&Lines];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+
+    #[test]
+    fn test_example() {
+        // 20 boxes have only 190 pairs, so connect fewer than the default
+        let args = ["--param=pairs=10".to_string()];
+        let params = Params::from_sources("day08", DEFAULTS, None, args).unwrap();
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(part1(input, &params), Ok(32));
+        assert_eq!(part2(input), Ok(2101473568));
+    }
+
+    #[test]
+    fn test_generate() {
+        let params = Params::from_sources("day08", DEFAULTS, None, Vec::new()).unwrap();
+        let input = generate(50, 1);
+        assert_eq!(parse_input(&input).unwrap().len(), 50);
+        assert!(run(&input, &params).is_ok());
+        assert_eq!(parse_input(&generate(0, 1)).unwrap().len(), 2);
+    }
 
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+    #[test]
+    fn test_limits() {
+        let params = Params::from_sources("day08", DEFAULTS, None, Vec::new()).unwrap();
+        let far = format!(
+            "{MAX_COORD},{MAX_COORD},{MAX_COORD}\n-{MAX_COORD},-{MAX_COORD},-{MAX_COORD}\n"
+        );
+        let square = (2 * MAX_COORD) * (2 * MAX_COORD);
+        assert_eq!(
+            sorted_pairs(&parse_input(far.as_bytes()).unwrap())[0].0,
+            3 * square
+        );
+        assert_eq!(
+            run(far.as_bytes(), &params),
+            Ok((2, -MAX_COORD * MAX_COORD))
+        );
+        assert_eq!(
+            part2(b"9000000000000000000,0,0\n-9000000000000000000,0,0\n"),
+            Err("expected x,y,z: \"9000000000000000000,0,0\"".to_string())
+        );
+
+        // The 64 shortest pairs make 64 circuits of two boxes
+        let boxes: String = (0..128)
+            .map(|i| format!("{},0,0\n", i / 2 * 100 + i % 2))
+            .collect();
+        let params = |top: usize| {
+            let args = ["--param=pairs=64".to_string(), format!("--param=top={top}")];
+            Params::from_sources("day08", DEFAULTS, None, args).unwrap()
+        };
+        assert_eq!(part1(boxes.as_bytes(), &params(62)), Ok(1 << 62));
+        assert_eq!(
+            part1(boxes.as_bytes(), &params(63)),
+            Err("product of the 63 largest circuits overflows i64".to_string())
+        );
+    }
+
+    /// The whole solver, not just the parser: the arithmetic on what it
+    /// accepts must not overflow either
+    #[test]
+    fn fuzz_run() {
+        let params = Params::from_sources("day08", DEFAULTS, None, Vec::new()).unwrap();
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(|input| run(input, &params));
+    }
 }
@@ -1,35 +1,63 @@
-use std::fs;
-
-fn parse_input(input: &str) -> Vec<(i64, i64)> {
-    input
-        .lines()
-        .filter_map(|line| {
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() == 2 {
-                Some((parts[0].parse().ok()?, parts[1].parse().ok()?))
-            } else {
-                None
-            }
-        })
-        .collect()
+use std::fmt::Write;
+
+use common::fuzz::Rng;
+use common::minimize::{non_empty_lines, Units};
+use common::scan::{self, Scanner};
+use common::svg::Svg;
+
+pub const SOLVER_VERSION: &str = "1";
+
+/// Coordinates are kept small enough that areas and the ray casting
+/// products fit in an i64
+const MAX_COORD: i64 = 1 << 30;
+
+fn parse_tile(line: &[u8]) -> Option<(i64, i64)> {
+    let mut scanner = Scanner::new(line);
+    let x = scanner.int()?;
+    scanner.expect(b',')?;
+    let y = scanner.int()?;
+    let in_range = |v: i64| (-MAX_COORD..=MAX_COORD).contains(&v);
+    (scanner.is_empty() && in_range(x) && in_range(y)).then_some((x, y))
+}
+
+fn parse_input(input: &[u8]) -> Result<Vec<(i64, i64)>, String> {
+    let tiles: Vec<(i64, i64)> = scan::lines(input)
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_tile(line).ok_or_else(|| format!("expected x,y: {:?}", scan::show(line))))
+        .collect::<Result<_, _>>()?;
+
+    if tiles.len() < 2 {
+        return Err("need at least two red tiles".to_string());
+    }
+    Ok(tiles)
 }
 
 fn rectangle_area((x1, y1): (i64, i64), (x2, y2): (i64, i64)) -> i64 {
     ((x2 - x1).abs() + 1) * ((y2 - y1).abs() + 1)
 }
 
-fn part1(tiles: &[(i64, i64)]) -> i64 {
+/// The two tiles that are the corners of the largest rectangle
+fn largest_rectangle(tiles: &[(i64, i64)]) -> ((i64, i64), (i64, i64)) {
     let n = tiles.len();
+    let mut corners = (tiles[0], tiles[1]);
     let mut max_area = 0;
 
     for i in 0..n - 1 {
         for j in i + 1..n {
             let area = rectangle_area(tiles[i], tiles[j]);
-            max_area = max_area.max(area);
+            if area > max_area {
+                max_area = area;
+                corners = (tiles[i], tiles[j]);
+            }
         }
     }
 
-    max_area
+    corners
+}
+
+fn solve_part1(tiles: &[(i64, i64)]) -> i64 {
+    let (a, b) = largest_rectangle(tiles);
+    rectangle_area(a, b)
 }
 
 // Point-in-polygon using ray casting algorithm
@@ -85,7 +113,7 @@ fn is_inside_or_on_polygon(point: (i64, i64), polygon: &[(i64, i64)]) -> bool {
     is_inside_polygon(point, polygon) || is_on_polygon_boundary(point, polygon)
 }
 
-fn part2(tiles: &[(i64, i64)]) -> i64 {
+fn solve_part2(tiles: &[(i64, i64)]) -> i64 {
     let n = tiles.len();
     let mut max_area = 0;
 
@@ -144,20 +172,152 @@ fn part2(tiles: &[(i64, i64)]) -> i64 {
     max_area
 }
 
-fn main() {
-    let input = fs::read_to_string("input.txt").expect("Failed to read input file");
-    let tiles = parse_input(&input);
+pub fn part1(input: &[u8]) -> Result<i64, String> {
+    Ok(solve_part1(&parse_input(input)?))
+}
+
+pub fn part2(input: &[u8]) -> Result<i64, String> {
+    Ok(solve_part2(&parse_input(input)?))
+}
+
+/// Both answers for `input`
+pub fn run(input: &[u8]) -> Result<(i64, i64), String> {
+    let tiles = parse_input(input)?;
+    Ok((solve_part1(&tiles), solve_part2(&tiles)))
+}
+
+/// A random skyline of about `size` red tiles: columns of random widths and
+/// heights standing on the x axis, so the loop never crosses itself
+pub fn generate(size: usize, seed: u64) -> Vec<u8> {
+    let mut rng = Rng::new(seed);
+    let mut tiles = vec![(0, 0)];
+    let (mut x, mut height) = (0, 0);
+    for _ in 0..(size / 2).saturating_sub(1).max(1) {
+        // Neighbouring columns differ in height, so every tile is a corner
+        let mut next = 1 + rng.below(100_000);
+        if next == height {
+            next += 1;
+        }
+        height = next;
+        tiles.push((x, height));
+        x += 1 + rng.below(1_000);
+        tiles.push((x, height));
+    }
+    tiles.push((x, 0));
+
+    let mut input = String::new();
+    for (x, y) in tiles {
+        let _ = writeln!(input, "{x},{y}");
+    }
+    input.into_bytes()
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_input(input).map(drop)
+}
+
+/// The loop as SVG: the area it encloses in green, the red tiles as dots
+/// and part 1's largest rectangle outlined
+pub fn svg(input: &[u8]) -> Result<String, String> {
+    let tiles = parse_input(input)?;
+    let xs = || tiles.iter().map(|&(x, _)| x as f64);
+    let ys = || tiles.iter().map(|&(_, y)| y as f64);
+    let (min_x, max_x) = (xs().fold(f64::MAX, f64::min), xs().fold(f64::MIN, f64::max));
+    let (min_y, max_y) = (ys().fold(f64::MAX, f64::min), ys().fold(f64::MIN, f64::max));
+    // Tiles are cells, so the drawing runs to the far edge of the last one
+    let extent = (max_x - min_x + 1.0).max(max_y - min_y + 1.0);
+    let (pad, dot) = (extent / 50.0, extent / 200.0);
+
+    let mut svg = Svg::with_origin(
+        min_x - pad,
+        min_y - pad,
+        max_x - min_x + 1.0 + 2.0 * pad,
+        max_y - min_y + 1.0 + 2.0 * pad,
+    );
+    let centre = |(x, y): (i64, i64)| (x as f64 + 0.5, y as f64 + 0.5);
+    let outline: Vec<(f64, f64)> = tiles.iter().map(|&tile| centre(tile)).collect();
+    svg.polygon(&outline, "#a9dfbf", "#27ae60", dot / 2.0);
 
-    let part1_result = part1(&tiles);
-    println!("Part 1: {}", part1_result);
+    let ((x1, y1), (x2, y2)) = largest_rectangle(&tiles);
+    let (left, top) = (x1.min(x2) as f64, y1.min(y2) as f64);
+    let (right, bottom) = (x1.max(x2) as f64 + 1.0, y1.max(y2) as f64 + 1.0);
+    let corners = [(left, top), (right, top), (right, bottom), (left, bottom)];
+    svg.polygon(&corners, "none", "#2e86c1", dot / 2.0);
 
-    let part2_result = part2(&tiles);
-    println!("Part 2: {}", part2_result);
+    for &tile in &tiles {
+        let (x, y) = centre(tile);
+        svg.circle(x, y, dot, "#c0392b");
+    }
+    svg.title(&format!(
+        "{} red tiles; largest rectangle {}",
+        tiles.len(),
+        rectangle_area((x1, y1), (x2, y2))
+    ));
+    Ok(svg.finish())
 }
 
+/// Minimizer units: consecutive vertex pairs of the loop. The kept vertex
+/// after a removed run of pairs is moved onto the line of the edge that
+/// led into the run, so a rectilinear loop stays rectilinear; vertices that
+/// don't parse, and diagonal edges, are left as they are.
+pub struct VertexPairs;
+
+/// `vertex` moved onto the axis-aligned line through `from` and `to`
+fn snap(from: &[u8], to: &[u8], vertex: &[u8]) -> Option<(i64, i64)> {
+    let (from, to, (x, y)) = (parse_tile(from)?, parse_tile(to)?, parse_tile(vertex)?);
+    if from.1 == to.1 {
+        Some((x, from.1))
+    } else if from.0 == to.0 {
+        Some((from.0, y))
+    } else {
+        None
+    }
+}
+
+impl Units for VertexPairs {
+    fn count(&self, input: &[u8]) -> usize {
+        non_empty_lines(input).count().div_ceil(2)
+    }
+
+    fn keep(&self, input: &[u8], kept: &[usize]) -> Vec<u8> {
+        let vertices: Vec<&[u8]> = non_empty_lines(input).collect();
+        let n = vertices.len();
+        let kept: Vec<usize> = kept
+            .iter()
+            .flat_map(|&pair| 2 * pair..(2 * pair + 2).min(n))
+            .collect();
+
+        let mut out = Vec::new();
+        for (i, &at) in kept.iter().enumerate() {
+            // The kept vertex before this one, round the loop; when the
+            // edge from it was removed, rejoin along that edge's axis. Only
+            // the coordinate that edge shares changes, which the edge out
+            // of the previous vertex doesn't depend on.
+            let before = kept[(i + kept.len() - 1) % kept.len()];
+            let rejoined = (before + 1) % n != at;
+            match rejoined
+                .then(|| snap(vertices[before], vertices[(before + 1) % n], vertices[at]))
+                .flatten()
+            {
+                Some((x, y)) => out.extend(format!("{x},{y}").bytes()),
+                None => out.extend_from_slice(vertices[at]),
+            }
+            out.push(b'\n');
+        }
+        out
+    }
+}
+
+/// This day's minimizer passes
+pub const UNITS: &[&dyn Units] = &[&VertexPairs];
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use common::fuzz::Fuzzer;
+    use common::minimize::minimize;
+    use common::snapshot::assert_snapshot;
 
     #[test]
     fn test_example() {
@@ -172,7 +332,85 @@ mod tests {
             (7, 3),
         ];
 
-        assert_eq!(part1(&tiles), 50);
-        assert_eq!(part2(&tiles), 24);
+        assert_eq!(solve_part1(&tiles), 50);
+        assert_eq!(solve_part2(&tiles), 24);
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = svg(include_bytes!("test_input.txt")).unwrap();
+        assert!(svg.contains("<title>8 red tiles; largest rectangle 50</title>"));
+        assert_eq!(svg.matches("<circle").count(), 8);
+        assert_eq!(svg.matches("<polygon").count(), 2);
+        assert_snapshot(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/test_input.svg"),
+            &svg,
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        let tiles = parse_input(&generate(50, 1)).unwrap();
+        assert_eq!(tiles.len(), 50);
+        let next = tiles.iter().cycle().skip(1);
+        for (a, b) in tiles.iter().zip(next) {
+            assert!((a.0 == b.0) != (a.1 == b.1), "{a:?} to {b:?}");
+        }
+        let (part1, part2) = run(&generate(50, 1)).unwrap();
+        assert!(0 < part2 && part2 <= part1);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_input(b""),
+            Err("need at least two red tiles".to_string())
+        );
+        assert_eq!(
+            parse_input(b"7,1\n"),
+            Err("need at least two red tiles".to_string())
+        );
+        assert_eq!(
+            parse_input(b"7,1\n11\n"),
+            Err("expected x,y: \"11\"".to_string())
+        );
+        assert!(parse_input(b"7,1\n1,2,3\n").is_err());
+        assert!(parse_input(b"7,1\n9999999999,1\n").is_err());
+        assert_eq!(run(b"7,1\n11,1\n"), Ok((5, 5)));
+    }
+
+    #[test]
+    fn test_shrink_vertex_pairs() {
+        let rectilinear = |s: &[u8]| {
+            let tiles = parse_input(s).unwrap();
+            let next = tiles.iter().cycle().skip(1);
+            tiles
+                .iter()
+                .zip(next)
+                .all(|(a, b)| a.0 == b.0 || a.1 == b.1)
+        };
+        let input = include_bytes!("test_input.txt");
+
+        let fails = |s: &[u8]| part2(s).is_ok_and(|area| area >= 15);
+        let minimal = minimize(input, UNITS, fails);
+        assert_eq!(minimal, b"7,5\n2,5\n2,3\n7,3\n");
+        assert!(rectilinear(&minimal));
+
+        // With the first and last pairs gone, 11,7 moves to 2,7 to close the loop
+        let fails = |s: &[u8]| non_empty_lines(s).any(|line| line == b"9,5");
+        let minimal = minimize(input, UNITS, fails);
+        assert_eq!(minimal, b"2,7\n9,7\n9,5\n2,5\n");
+        assert!(rectilinear(&minimal));
+
+        // An odd vertex out makes a pair of its own
+        assert_eq!(minimize(b"1,1\n2,2\n9,5\n", UNITS, fails), b"9,5\n");
+    }
+
+    #[test]
+    fn fuzz_parse_input() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(parse_input);
     }
 }
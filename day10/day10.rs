@@ -1,159 +1,107 @@
 use std::collections::HashMap;
-use std::fs;
+use std::hash::BuildHasher;
+
+use algo::gf2;
+use common::hash::FxBuildHasher;
+use common::minimize::{keeper, Lines, Units};
+use common::params::Params;
+use common::scan::{self, Int};
+
+pub const SOLVER_VERSION: &str = "2";
+
+pub const DEFAULTS: &[(&str, &str)] = &[
+    ("limit_multi", "200"),
+    ("limit_single", "20000"),
+    ("max_free", "4"),
+];
+
+/// Per-free-variable search bounds for part 2, and how many free variables
+/// it takes on at all: the search tries `limit_multi + 1` values of each
+#[derive(Debug, Clone, Copy)]
+struct SearchLimits {
+    multi: i64,
+    single: i64,
+    max_free: usize,
+}
+
+fn overflow() -> String {
+    "joltage arithmetic overflows i128".to_string()
+}
+
+/// A comma-separated list of numbers between `open` and `close`, like
+/// `(0,3)` or `{3,5,4,7}`; `None` on anything else
+fn parse_list<T: Int>(token: &[u8], open: u8, close: u8) -> Option<Vec<T>> {
+    let inner = token.strip_prefix(&[open])?.strip_suffix(&[close])?;
+    if inner.is_empty() {
+        return Some(Vec::new());
+    }
+    inner.split(|&b| b == b',').map(scan::parse).collect()
+}
 
 #[derive(Debug, Clone)]
 struct Machine {
     target_lights: Vec<u8>,
     buttons: Vec<Vec<usize>>,
     joltages: Vec<i64>,
-    num_lights: usize,
 }
 
-fn parse_input(input: &str) -> Vec<Machine> {
-    input
-        .lines()
-        .filter(|line| !line.trim().is_empty())
+fn parse_input(input: &[u8]) -> Result<Vec<Machine>, String> {
+    scan::lines(input)
+        .filter(|line| !line.trim_ascii().is_empty())
         .map(|line| {
-            let parts: Vec<&str> = line.split_whitespace().collect();
+            let parts: Vec<&[u8]> = scan::words(line).collect();
+            let [lights_part, button_parts @ .., joltage_part] = &parts[..] else {
+                return Err(format!(
+                    "expected [lights] (buttons) {{joltages}}: {:?}",
+                    scan::show(line)
+                ));
+            };
 
             // Parse indicator lights pattern [.##.]
-            let lights_str = parts[0].trim_matches(|c| c == '[' || c == ']');
-            let num_lights = lights_str.len();
+            let lights_str = lights_part
+                .strip_prefix(b"[")
+                .and_then(|s| s.strip_suffix(b"]"))
+                .ok_or_else(|| format!("invalid light pattern: {:?}", scan::show(lights_part)))?;
             let target_lights: Vec<u8> = lights_str
-                .chars()
-                .map(|c| if c == '#' { 1 } else { 0 })
-                .collect();
+                .iter()
+                .map(|&c| match c {
+                    b'#' => Ok(1),
+                    b'.' => Ok(0),
+                    _ => Err(format!(
+                        "invalid light {:?} in {:?}",
+                        c as char,
+                        scan::show(lights_part)
+                    )),
+                })
+                .collect::<Result<_, _>>()?;
 
             // Parse buttons (0,1,2)
-            let buttons: Vec<Vec<usize>> = parts[1..parts.len() - 1]
+            let buttons: Vec<Vec<usize>> = button_parts
                 .iter()
-                .map(|button_str| {
-                    button_str
-                        .trim_matches(|c| c == '(' || c == ')')
-                        .split(',')
-                        .filter_map(|s| s.parse().ok())
-                        .collect()
+                .map(|button| {
+                    parse_list(button, b'(', b')')
+                        .ok_or_else(|| format!("invalid button: {:?}", scan::show(button)))
                 })
-                .collect();
+                .collect::<Result<_, _>>()?;
 
             // Parse joltages {3,5,4,7}
-            let joltages: Vec<i64> = parts
-                .last()
-                .unwrap()
-                .trim_matches(|c| c == '{' || c == '}')
-                .split(',')
-                .filter_map(|s| s.parse().ok())
-                .collect();
+            let joltages: Vec<i64> = parse_list(joltage_part, b'{', b'}')
+                .ok_or_else(|| format!("invalid joltages: {:?}", scan::show(joltage_part)))?;
 
-            Machine {
+            Ok(Machine {
                 target_lights,
                 buttons,
                 joltages,
-                num_lights,
-            }
+            })
         })
         .collect()
 }
 
-fn solve_part1(target: &[u8], buttons: &[Vec<usize>], num_lights: usize) -> Option<usize> {
-    let num_buttons = buttons.len();
-
-    // Build matrix: rows are lights, columns are buttons
-    // matrix[light][button] = 1 if button affects light
-    let mut matrix = vec![vec![0u8; num_buttons + 1]; num_lights];
-
-    for (button_idx, button) in buttons.iter().enumerate() {
-        for &light in button {
-            if light < num_lights {
-                matrix[light][button_idx] = 1;
-            }
-        }
-    }
-
-    // Set target values in augmented column
-    for (light, &val) in target.iter().enumerate() {
-        matrix[light][num_buttons] = val;
-    }
-
-    // Gaussian elimination to RREF over GF(2)
-    let mut pivot_row = 0;
-    let mut pivot_col_to_row = HashMap::new();
-
-    for col in 0..num_buttons {
-        if pivot_row >= num_lights {
-            break;
-        }
-
-        // Find pivot in current column
-        let mut row = pivot_row;
-        while row < num_lights && matrix[row][col] == 0 {
-            row += 1;
-        }
-
-        if row < num_lights {
-            // Swap rows to bring pivot to position
-            matrix.swap(pivot_row, row);
-
-            // Eliminate all other rows using XOR (GF(2) addition)
-            for r in 0..num_lights {
-                if r != pivot_row && matrix[r][col] == 1 {
-                    for k in col..=num_buttons {
-                        matrix[r][k] ^= matrix[pivot_row][k];
-                    }
-                }
-            }
-
-            pivot_col_to_row.insert(col, pivot_row);
-            pivot_row += 1;
-        }
-    }
-
-    // Check for inconsistency
-    for row in &matrix[pivot_row..num_lights] {
-        if row[num_buttons] == 1 {
-            return None;
-        }
-    }
-
-    // Identify free variables
-    let free_vars: Vec<usize> = (0..num_buttons)
-        .filter(|col| !pivot_col_to_row.contains_key(col))
-        .collect();
-
-    // Try all combinations to find minimum Hamming weight
-    let num_free = free_vars.len();
-    let mut min_presses = usize::MAX;
-
-    for mask in 0..(1u64 << num_free) {
-        let mut solution = vec![0u8; num_buttons];
-
-        // Set free variables based on bitmask
-        for (bit_idx, &var_idx) in free_vars.iter().enumerate() {
-            if (mask >> bit_idx) & 1 == 1 {
-                solution[var_idx] = 1;
-            }
-        }
-
-        // Back-substitute to find pivot variable values
-        for (&pivot_col, &pivot_row) in &pivot_col_to_row {
-            let mut val = matrix[pivot_row][num_buttons];
-            for &free_var in &free_vars {
-                if matrix[pivot_row][free_var] == 1 {
-                    val ^= solution[free_var];
-                }
-            }
-            solution[pivot_col] = val;
-        }
-
-        // Count button presses
-        let presses = solution.iter().filter(|&&v| v == 1).count();
-        min_presses = min_presses.min(presses);
-    }
-
-    Some(min_presses)
-}
-
-fn solve_part2(target: &[i64], buttons: &[Vec<usize>]) -> Option<i64> {
+fn solve_part2<S: BuildHasher + Default>(
+    target: &[i64],
+    buttons: &[Vec<usize>],
+    limits: SearchLimits,
+) -> Result<Option<i64>, String> {
     let num_requirements = target.len();
     let num_buttons = buttons.len();
 
@@ -175,7 +123,7 @@ fn solve_part2(target: &[i64], buttons: &[Vec<usize>]) -> Option<i64> {
 
     // Fraction-free Gaussian elimination (forward pass only)
     let mut pivot_row = 0;
-    let mut pivot_col_to_row = HashMap::new();
+    let mut pivot_col_to_row: HashMap<usize, usize, S> = HashMap::default();
 
     for col in 0..num_buttons {
         if pivot_row >= num_requirements {
@@ -194,11 +142,17 @@ fn solve_part2(target: &[i64], buttons: &[Vec<usize>]) -> Option<i64> {
             let pivot_val = matrix[pivot_row][col];
 
             // Eliminate rows below
-            for r in pivot_row + 1..num_requirements {
-                if matrix[r][col] != 0 {
-                    let factor = matrix[r][col];
-                    for k in col..=num_buttons {
-                        matrix[r][k] = matrix[r][k] * pivot_val - matrix[pivot_row][k] * factor;
+            let (above, below) = matrix.split_at_mut(pivot_row + 1);
+            let pivot = &above[pivot_row];
+            for row in below {
+                if row[col] != 0 {
+                    let factor = row[col];
+                    for (cell, &p) in row[col..].iter_mut().zip(&pivot[col..]) {
+                        *cell = cell
+                            .checked_mul(pivot_val)
+                            .zip(p.checked_mul(factor))
+                            .and_then(|(scaled, eliminated)| scaled.checked_sub(eliminated))
+                            .ok_or_else(overflow)?;
                     }
                 }
             }
@@ -211,7 +165,7 @@ fn solve_part2(target: &[i64], buttons: &[Vec<usize>]) -> Option<i64> {
     // Check for inconsistency
     for row in &matrix[pivot_row..num_requirements] {
         if row[num_buttons] != 0 {
-            return None;
+            return Ok(None);
         }
     }
 
@@ -219,20 +173,29 @@ fn solve_part2(target: &[i64], buttons: &[Vec<usize>]) -> Option<i64> {
     let free_vars: Vec<usize> = (0..num_buttons)
         .filter(|col| !pivot_col_to_row.contains_key(col))
         .collect();
+    if free_vars.len() > limits.max_free {
+        return Err(format!(
+            "{} free variables, over max_free {}",
+            free_vars.len(),
+            limits.max_free
+        ));
+    }
 
     // Search for minimum solution
     let mut min_total: Option<i64> = None;
 
-    fn search(
+    #[allow(clippy::too_many_arguments)]
+    fn search<S: BuildHasher>(
         free_idx: usize,
         free_vars: &[usize],
         free_vals: &mut Vec<i64>,
         matrix: &[Vec<i128>],
-        row_to_pivot_col: &HashMap<usize, usize>,
+        row_to_pivot_col: &HashMap<usize, usize, S>,
         num_buttons: usize,
         num_pivots: usize,
+        limit: i64,
         min_total: &mut Option<i64>,
-    ) {
+    ) -> Result<(), String> {
         if free_idx == free_vars.len() {
             // Try to solve with current free variable values
             let mut solution = vec![0i128; num_buttons];
@@ -250,17 +213,20 @@ fn solve_part2(target: &[i64], buttons: &[Vec<usize>]) -> Option<i64> {
 
                     // Subtract known variable contributions
                     for col in pivot_col + 1..num_buttons {
-                        rhs -= matrix[row][col] * solution[col];
+                        rhs = matrix[row][col]
+                            .checked_mul(solution[col])
+                            .and_then(|known| rhs.checked_sub(known))
+                            .ok_or_else(overflow)?;
                     }
 
                     // Check if solution is integral and non-negative
-                    if rhs % pivot_val != 0 {
-                        return; // Not integral
+                    if rhs.checked_rem(pivot_val).ok_or_else(overflow)? != 0 {
+                        return Ok(()); // Not integral
                     }
 
                     let val = rhs / pivot_val;
                     if val < 0 {
-                        return; // Negative solution
+                        return Ok(()); // Negative solution
                     }
 
                     solution[pivot_col] = val;
@@ -268,18 +234,17 @@ fn solve_part2(target: &[i64], buttons: &[Vec<usize>]) -> Option<i64> {
             }
 
             // Calculate total button presses
-            let total: i128 = solution.iter().sum();
-            if total >= 0 && total < i64::MAX as i128 {
-                let total_i64 = total as i64;
-                if min_total.is_none() || total_i64 < min_total.unwrap() {
-                    *min_total = Some(total_i64);
-                }
+            let total = solution
+                .iter()
+                .try_fold(0i128, |total, &presses| total.checked_add(presses))
+                .and_then(|total| i64::try_from(total).ok())
+                .ok_or("button presses overflow i64")?;
+            if min_total.is_none_or(|min| total < min) {
+                *min_total = Some(total);
             }
-            return;
+            return Ok(());
         }
 
-        // Search range: larger for single free var, smaller for multiple
-        let limit = if free_vars.len() > 1 { 200 } else { 20000 };
         for val in 0..=limit {
             free_vals[free_idx] = val;
             search(
@@ -290,18 +255,27 @@ fn solve_part2(target: &[i64], buttons: &[Vec<usize>]) -> Option<i64> {
                 row_to_pivot_col,
                 num_buttons,
                 num_pivots,
+                limit,
                 min_total,
-            );
+            )?;
         }
+        Ok(())
     }
 
     // Build row -> pivot_col map once for back-substitution
-    let mut row_to_pivot_col = HashMap::new();
+    let mut row_to_pivot_col: HashMap<usize, usize, S> = HashMap::default();
     for (&col, &row) in &pivot_col_to_row {
         row_to_pivot_col.insert(row, col);
     }
     let num_pivots = pivot_col_to_row.len();
 
+    // Search range: larger for single free var, smaller for multiple
+    let limit = if free_vars.len() > 1 {
+        limits.multi
+    } else {
+        limits.single
+    };
+
     let mut free_vals = vec![0i64; free_vars.len()];
     search(
         0,
@@ -311,37 +285,198 @@ fn solve_part2(target: &[i64], buttons: &[Vec<usize>]) -> Option<i64> {
         &row_to_pivot_col,
         num_buttons,
         num_pivots,
+        limit,
         &mut min_total,
-    );
+    )?;
 
-    min_total
+    Ok(min_total)
 }
 
-fn main() {
-    let input = fs::read_to_string("input.txt").expect("Failed to read input.txt");
-    let machines = parse_input(&input);
+fn search_limits(params: &Params) -> Result<SearchLimits, String> {
+    Ok(SearchLimits {
+        multi: params.get("limit_multi")?,
+        single: params.get("limit_single")?,
+        max_free: params.get("max_free")?,
+    })
+}
 
-    // Part 1: Binary configuration using Gaussian elimination over GF(2)
-    let part1_total: usize = machines
+fn solve_lights(machines: &[Machine]) -> Result<usize, String> {
+    machines
         .iter()
-        .filter_map(|machine| {
-            solve_part1(&machine.target_lights, &machine.buttons, machine.num_lights)
+        .zip(1..)
+        .try_fold(0, |total, (machine, line)| {
+            match gf2::min_weight_solution(&machine.target_lights, &machine.buttons) {
+                Ok(presses) => Ok(total + presses.unwrap_or(0)),
+                Err(free) => Err(format!(
+                    "machine {line}: {free} free variables in the lights, over {}",
+                    gf2::MAX_FREE_VARS
+                )),
+            }
         })
-        .sum();
-
-    println!("Part 1: {}", part1_total);
+}
 
-    // Part 2: Integer linear programming with branch-and-bound search
-    let part2_total: i64 = machines
+fn solve_joltages<S: BuildHasher + Default>(
+    machines: &[Machine],
+    limits: SearchLimits,
+) -> Result<i64, String> {
+    machines
         .iter()
-        .filter_map(|machine| {
-            if !machine.joltages.is_empty() {
-                solve_part2(&machine.joltages, &machine.buttons)
+        .zip(1..)
+        .try_fold(0i64, |total, (machine, line)| {
+            let presses = if !machine.joltages.is_empty() {
+                solve_part2::<S>(&machine.joltages, &machine.buttons, limits)
+                    .map_err(|err| format!("machine {line}: {err}"))?
             } else {
                 Some(0)
-            }
+            };
+            total
+                .checked_add(presses.unwrap_or(0))
+                .ok_or_else(|| "total button presses overflow i64".to_string())
         })
-        .sum();
+}
 
-    println!("Part 2: {}", part2_total);
+/// Binary configuration using Gaussian elimination over GF(2)
+pub fn part1(input: &[u8]) -> Result<usize, String> {
+    solve_lights(&parse_input(input)?)
+}
+
+/// Integer linear programming with branch-and-bound search
+pub fn part2(input: &[u8], params: &Params) -> Result<i64, String> {
+    part2_with_hasher::<FxBuildHasher>(input, params)
+}
+
+/// `part2` with the pivot maps hashed by `S`, for the hash benchmark
+pub fn part2_with_hasher<S: BuildHasher + Default>(
+    input: &[u8],
+    params: &Params,
+) -> Result<i64, String> {
+    let limits = search_limits(params)?;
+    solve_joltages::<S>(&parse_input(input)?, limits)
+}
+
+/// Both answers for `input`
+pub fn run(input: &[u8], params: &Params) -> Result<(usize, i64), String> {
+    let limits = search_limits(params)?;
+    let machines = parse_input(input)?;
+    Ok((
+        solve_lights(&machines)?,
+        solve_joltages::<FxBuildHasher>(&machines, limits)?,
+    ))
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_input(input).map(drop)
+}
+
+/// Minimizer units: individual buttons across all machines (whole
+/// machines are removed first by the plain `Lines` pass)
+pub struct Buttons;
+
+fn is_button(token: &[u8]) -> bool {
+    token.starts_with(b"(")
+}
+
+impl Units for Buttons {
+    fn count(&self, input: &[u8]) -> usize {
+        scan::words(input).filter(|t| is_button(t)).count()
+    }
+
+    fn keep(&self, input: &[u8], kept: &[usize]) -> Vec<u8> {
+        let mut keep = keeper(kept);
+        let mut out = Vec::new();
+        for line in input.split(|&b| b == b'\n') {
+            let tokens: Vec<&[u8]> = scan::words(line)
+                .filter(|token| !is_button(token) || keep())
+                .collect();
+            if !tokens.is_empty() {
+                out.extend(tokens.join(&b' '));
+                out.push(b'\n');
+            }
+        }
+        out
+    }
+}
+
+/// This day's minimizer passes: whole machines, then single buttons
+pub const UNITS: &[&dyn Units] = &[&Lines, &Buttons];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+    use common::minimize::minimize;
+
+    #[test]
+    fn test_example() {
+        let params = Params::from_sources("day10", DEFAULTS, None, Vec::new()).unwrap();
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(part1(input), Ok(8));
+        assert_eq!(part2(input, &params), Ok(374));
+    }
+
+    #[test]
+    fn test_limits() {
+        let params = Params::from_sources("day10", DEFAULTS, None, Vec::new()).unwrap();
+        assert_eq!(
+            part1(b"[#] (0) (x) {1}\n"),
+            Err("invalid button: \"(x)\"".to_string())
+        );
+        assert_eq!(
+            part1(b"[#] (0,) {1}\n"),
+            Err("invalid button: \"(0,)\"".to_string())
+        );
+        assert_eq!(
+            part1(b"[#] (0) {1,two}\n"),
+            Err("invalid joltages: \"{1,two}\"".to_string())
+        );
+        assert_eq!(run(b"[#] () (0) {}\n", &params), Ok((1, 0)));
+
+        // One pivot, and a free variable for every other button
+        let same = |buttons: usize| format!("[#] {}{{1}}\n", "(0) ".repeat(buttons));
+        assert_eq!(part1(same(gf2::MAX_FREE_VARS + 1).as_bytes()), Ok(1));
+        assert_eq!(
+            part1(same(gf2::MAX_FREE_VARS + 2).as_bytes()),
+            Err("machine 1: 21 free variables in the lights, over 20".to_string())
+        );
+        let args = ["--param=limit_multi=2".to_string()];
+        let small = Params::from_sources("day10", DEFAULTS, None, args).unwrap();
+        assert_eq!(part2(same(5).as_bytes(), &small), Ok(1));
+        assert_eq!(
+            part2(same(6).as_bytes(), &small),
+            Err("machine 1: 5 free variables, over max_free 4".to_string())
+        );
+
+        // Elimination without division grows the coefficients past i128
+        let overflowing = concat!(
+            "[................] (2,3,4,6,11,12,15) (0,1,4,7,8,9,10,12,13,14) ",
+            "(2,4,6,7,8,10,13,14,15) (0,1,2,5,6,10,12,13,14) (0,2,3,4,5,7,8,10,14,15) ",
+            "(0,1,2,3,5,7,10,11,12,14,15) (3,5,6,8,9,10,11,12,13,14) (1,4,5,8,9,10,12,15) ",
+            "(0,2,3,4,5,6,11,12,14,15) (2,3,4,5,7,8,9,11,13,14) (3,15) (0,1,6,11,13) ",
+            "(0,3,4,5,11,13,14) (0,1,8,10,11,14) (1,5,6,7,8,12,14,15) (2,3,4,5,9,13,14) ",
+            "{962,959,777,829,671,945,516,535,211,889,896,230,929,123,69,772}\n",
+        );
+        assert_eq!(
+            part2(overflowing.as_bytes(), &params),
+            Err("machine 1: joltage arithmetic overflows i128".to_string())
+        );
+    }
+
+    #[test]
+    fn test_shrink_buttons() {
+        let fails = |s: &[u8]| scan::words(s).any(|token| token == b"(1,3)");
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(
+            minimize(input, UNITS, fails),
+            b"[#.#..#] (1,3) {11,32,30,9,24,17}\n"
+        );
+    }
+
+    #[test]
+    fn fuzz_parse_input() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(parse_input);
+    }
 }
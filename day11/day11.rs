@@ -2,113 +2,367 @@
 // Part 1: Count paths from "you" to "out"
 // Part 2: Count paths from "svr" to "out" visiting both "dac" and "fft"
 
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
 
-type Graph<'a> = HashMap<&'a str, Vec<&'a str>>;
+use common::hash::FxBuildHasher;
+use common::minimize::{keeper, non_empty_lines, Units};
+use common::params::Params;
+use common::scan;
 
-fn parse_graph(input: &str) -> Graph<'_> {
-    input
-        .lines()
+pub const SOLVER_VERSION: &str = "1";
+
+pub const DEFAULTS: &[(&str, &str)] = &[
+    ("start", "you"),
+    ("server", "svr"),
+    ("goal", "out"),
+    ("via", "dac,fft"),
+];
+
+// Node names stay slices of the input buffer
+type Node<'a> = &'a [u8];
+type Graph<'a, S = FxBuildHasher> = HashMap<Node<'a>, Vec<Node<'a>>, S>;
+
+fn parse_graph<S: BuildHasher + Default>(input: &[u8]) -> Result<Graph<'_, S>, String> {
+    scan::lines(input)
         .filter(|line| !line.is_empty())
         .map(|line| {
-            let (src, dests) = line.split_once(": ").unwrap();
-            (src, dests.split_whitespace().collect())
+            let (src, dests) = scan::split_once(line, b':')
+                .ok_or_else(|| format!("expected node: targets, got {:?}", scan::show(line)))?;
+            Ok((src, scan::words(dests).collect()))
         })
         .collect()
 }
 
-struct PathCounter<'a> {
-    graph: &'a Graph<'a>,
-    goal: &'a str,
-    memo: HashMap<&'a str, i64>,
+/// What a state of a walk is worth on its own, or the states it leads to
+enum Step<K> {
+    Leaf(i64),
+    Next(Vec<K>),
+}
+
+/// A state whose successors are still being added up
+struct Frame<K> {
+    state: K,
+    next: Vec<K>,
+    total: i64,
+}
+
+/// Adds up the leaves of every walk from `start`, memoized per state.
+/// Iterative, as a path can be as long as the graph; a state met again on
+/// its own path is a cycle, which would make the count endless.
+fn count_walks<K, S>(
+    start: K,
+    memo: &mut HashMap<K, i64, S>,
+    step: impl Fn(K) -> Step<K>,
+    node: impl Fn(K) -> String,
+) -> Result<i64, String>
+where
+    K: Copy + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    let mut on_path: HashSet<K, S> = HashSet::default();
+    let mut stack: Vec<Frame<K>> = Vec::new();
+    let mut pending = Some(start);
+
+    loop {
+        // The value of the state just reached, unless it has to be walked
+        let mut value = None;
+        if let Some(state) = pending.take() {
+            match memo.get(&state) {
+                Some(&count) => value = Some(count),
+                None => match step(state) {
+                    Step::Leaf(count) => value = Some(count),
+                    Step::Next(next) => {
+                        if !on_path.insert(state) {
+                            return Err(format!("cycle through {}", node(state)));
+                        }
+                        stack.push(Frame {
+                            state,
+                            next,
+                            total: 0,
+                        });
+                    }
+                },
+            }
+        } else if let Some(frame) = stack.last_mut() {
+            match frame.next.pop() {
+                Some(next) => pending = Some(next),
+                None => {
+                    let Frame { state, total, .. } = stack.pop().expect("a frame is on top");
+                    on_path.remove(&state);
+                    memo.insert(state, total);
+                    value = Some(total);
+                }
+            }
+        }
+
+        if let Some(value) = value {
+            let Some(frame) = stack.last_mut() else {
+                return Ok(value);
+            };
+            frame.total = frame
+                .total
+                .checked_add(value)
+                .ok_or("path count overflows i64")?;
+        }
+    }
+}
+
+fn name(node: Node) -> String {
+    scan::show(node).into_owned()
 }
 
-impl<'a> PathCounter<'a> {
-    fn new(graph: &'a Graph<'a>, goal: &'a str) -> Self {
+struct PathCounter<'a, S> {
+    graph: &'a Graph<'a, S>,
+    goal: Node<'a>,
+    memo: HashMap<Node<'a>, i64, S>,
+}
+
+impl<'a, S: BuildHasher + Default> PathCounter<'a, S> {
+    fn new(graph: &'a Graph<'a, S>, goal: Node<'a>) -> Self {
         Self {
             graph,
             goal,
-            memo: HashMap::new(),
+            memo: HashMap::default(),
         }
     }
 
-    fn count(&mut self, node: &'a str) -> i64 {
-        if node == self.goal {
-            return 1;
-        }
-        if let Some(&count) = self.memo.get(node) {
-            return count;
-        }
-
-        let count = self
-            .graph
-            .get(node)
-            .map(|neighbors| neighbors.iter().map(|n| self.count(n)).sum())
-            .unwrap_or(0);
-
-        self.memo.insert(node, count);
-        count
+    fn count(&mut self, start: Node<'a>) -> Result<i64, String> {
+        let (graph, goal) = (self.graph, self.goal);
+        let step = |node: Node<'a>| match graph.get(node) {
+            _ if node == goal => Step::Leaf(1),
+            Some(neighbors) => Step::Next(neighbors.clone()),
+            None => Step::Leaf(0),
+        };
+        count_walks(start, &mut self.memo, step, name)
     }
 }
 
-struct PathCounterVia<'a> {
-    graph: &'a Graph<'a>,
-    goal: &'a str,
-    required: [&'a str; 2],
-    memo: HashMap<(&'a str, u8), i64>,
+struct PathCounterVia<'a, S> {
+    graph: &'a Graph<'a, S>,
+    goal: Node<'a>,
+    required: &'a [Node<'a>],
+    memo: HashMap<(Node<'a>, u8), i64, S>,
 }
 
-impl<'a> PathCounterVia<'a> {
-    fn new(graph: &'a Graph<'a>, goal: &'a str, required: [&'a str; 2]) -> Self {
+impl<'a, S: BuildHasher + Default> PathCounterVia<'a, S> {
+    /// At most 8 `required` nodes, as they are tracked in a `u8` mask
+    fn new(graph: &'a Graph<'a, S>, goal: Node<'a>, required: &'a [Node<'a>]) -> Self {
         Self {
             graph,
             goal,
             required,
-            memo: HashMap::new(),
+            memo: HashMap::default(),
         }
     }
 
-    fn count(&mut self, node: &'a str, mask: u8) -> i64 {
-        let mask =
-            self.required.iter().enumerate().fold(
-                mask,
-                |m, (i, &req)| {
-                    if node == req {
-                        m | (1 << i)
-                    } else {
-                        m
-                    }
-                },
-            );
+    /// `mask` with `node` marked if it is a required one
+    fn visit(required: &[Node], node: Node, mask: u8) -> u8 {
+        required
+            .iter()
+            .enumerate()
+            .filter(|&(_, &req)| node == req)
+            .fold(mask, |mask, (i, _)| mask | (1 << i))
+    }
 
-        if node == self.goal {
-            return if mask == 3 { 1 } else { 0 };
-        }
+    fn count(&mut self, start: Node<'a>) -> Result<i64, String> {
+        let (graph, goal, required) = (self.graph, self.goal, self.required);
+        let all_visited = ((1u16 << required.len()) - 1) as u8;
+        let step = |(node, mask): (Node<'a>, u8)| match graph.get(node) {
+            _ if node == goal => Step::Leaf((mask == all_visited) as i64),
+            Some(neighbors) => Step::Next(
+                neighbors
+                    .iter()
+                    .map(|&next| (next, Self::visit(required, next, mask)))
+                    .collect(),
+            ),
+            None => Step::Leaf(0),
+        };
+        let start = (start, Self::visit(required, start, 0));
+        count_walks(start, &mut self.memo, step, |(node, _)| name(node))
+    }
+}
 
-        let key = (node, mask);
-        if let Some(&count) = self.memo.get(&key) {
-            return count;
-        }
+/// The part 2 route: from `server` to `goal` through every `via` node
+struct Route<'a> {
+    server: Node<'a>,
+    goal: Node<'a>,
+    via: Vec<Node<'a>>,
+}
 
-        let count = self
-            .graph
-            .get(node)
-            .map(|neighbors| neighbors.iter().map(|n| self.count(n, mask)).sum())
-            .unwrap_or(0);
+fn route(params: &Params) -> Result<Route<'_>, String> {
+    let via: Vec<Node> = params.list("via").into_iter().map(str::as_bytes).collect();
+    if via.len() > 8 {
+        return Err(format!(
+            "at most 8 via nodes are supported, got {}",
+            via.len()
+        ));
+    }
+    Ok(Route {
+        server: params.raw("server").as_bytes(),
+        goal: params.raw("goal").as_bytes(),
+        via,
+    })
+}
 
-        self.memo.insert(key, count);
-        count
+fn solve_part1<S: BuildHasher + Default>(graph: &Graph<S>, params: &Params) -> Result<i64, String> {
+    let (start, goal) = (
+        params.raw("start").as_bytes(),
+        params.raw("goal").as_bytes(),
+    );
+    PathCounter::new(graph, goal).count(start)
+}
+
+fn solve_part2<S: BuildHasher + Default>(graph: &Graph<S>, route: &Route) -> Result<i64, String> {
+    PathCounterVia::new(graph, route.goal, &route.via).count(route.server)
+}
+
+/// Paths from `start` to `goal`
+pub fn part1(input: &[u8], params: &Params) -> Result<i64, String> {
+    part1_with_hasher::<FxBuildHasher>(input, params)
+}
+
+/// Paths from `server` to `goal` that pass through every `via` node
+pub fn part2(input: &[u8], params: &Params) -> Result<i64, String> {
+    part2_with_hasher::<FxBuildHasher>(input, params)
+}
+
+/// `part1` with the graph and memo hashed by `S`, for the hash benchmark
+pub fn part1_with_hasher<S: BuildHasher + Default>(
+    input: &[u8],
+    params: &Params,
+) -> Result<i64, String> {
+    solve_part1(&parse_graph::<S>(input)?, params)
+}
+
+/// `part2` with the graph and memo hashed by `S`, for the hash benchmark
+pub fn part2_with_hasher<S: BuildHasher + Default>(
+    input: &[u8],
+    params: &Params,
+) -> Result<i64, String> {
+    let route = route(params)?;
+    solve_part2(&parse_graph::<S>(input)?, &route)
+}
+
+/// Both answers for `input`
+pub fn run(input: &[u8], params: &Params) -> Result<(i64, i64), String> {
+    let route = route(params)?;
+    let graph = parse_graph::<FxBuildHasher>(input)?;
+    Ok((solve_part1(&graph, params)?, solve_part2(&graph, &route)?))
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parse_graph::<FxBuildHasher>(input).map(drop)
+}
+
+/// Minimizer units: single `src -> dest` edges; a node line is dropped
+/// once it has no edges left
+pub struct Edges;
+
+impl Units for Edges {
+    fn count(&self, input: &[u8]) -> usize {
+        non_empty_lines(input)
+            .filter_map(|line| scan::split_once(line, b':'))
+            .map(|(_, dests)| scan::words(dests).count())
+            .sum()
+    }
+
+    fn keep(&self, input: &[u8], kept: &[usize]) -> Vec<u8> {
+        let mut keep = keeper(kept);
+        let mut out = Vec::new();
+        for line in non_empty_lines(input) {
+            let Some((src, dests)) = scan::split_once(line, b':') else {
+                out.extend_from_slice(line);
+                out.push(b'\n');
+                continue;
+            };
+            let dests: Vec<&[u8]> = scan::words(dests).filter(|_| keep()).collect();
+            if !dests.is_empty() {
+                out.extend_from_slice(src);
+                out.extend_from_slice(b": ");
+                out.extend(dests.join(&b' '));
+                out.push(b'\n');
+            }
+        }
+        out
     }
 }
 
-fn main() {
-    let input = fs::read_to_string("day11/input.txt").expect("Failed to read input");
-    let graph = parse_graph(&input);
+/// This day's minimizer passes
+pub const UNITS: &[&dyn Units] = &[&Edges];
 
-    let part1 = PathCounter::new(&graph, "out").count("you");
-    let part2 = PathCounterVia::new(&graph, "out", ["dac", "fft"]).count("svr", 0);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+    use common::minimize::minimize;
+    use std::hash::RandomState;
 
-    println!("Part 1: {part1}");
-    println!("Part 2: {part2}");
+    #[test]
+    fn test_via() {
+        let params = |via: &str| {
+            let args = [format!("--param=via={via}")].into_iter();
+            Params::from_sources("day11", DEFAULTS, None, args).unwrap()
+        };
+        let input = b"you: a\nsvr: a b\na: out\nb: c\nc: out\n";
+        assert_eq!(run(input, &params("b,c")), Ok((1, 1)));
+        assert_eq!(
+            run(input, &params("a,b,c,d,e,f,g,h,i")),
+            Err("at most 8 via nodes are supported, got 9".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cycles_and_long_paths() {
+        let params = Params::from_sources("day11", DEFAULTS, None, Vec::new()).unwrap();
+        let cycle = b"you: a\na: you out\nsvr: out\n";
+        assert_eq!(part1(cycle, &params), Err("cycle through you".to_string()));
+        assert_eq!(part2(cycle, &params), Ok(0));
+        assert!(run(cycle, &params).is_err());
+
+        // Deeper than any call stack would go, hashed by std as FxHash
+        // spreads names this alike poorly
+        let mut chain = String::from("you: n0\nsvr: n0\n");
+        for i in 0..100_000 {
+            chain += &format!("n{i}: n{}\n", i + 1);
+        }
+        chain += "n100000: dac\ndac: fft\nfft: out\n";
+        let chain = chain.as_bytes();
+        assert_eq!(part1_with_hasher::<RandomState>(chain, &params), Ok(1));
+        assert_eq!(part2_with_hasher::<RandomState>(chain, &params), Ok(1));
+
+        // Every layer doubles the paths
+        let mut doubling = String::from("you: a0 b0\n");
+        for i in 0..64 {
+            doubling += &format!("a{i}: a{0} b{0}\nb{i}: a{0} b{0}\n", i + 1);
+        }
+        doubling += "a64: out\nb64: out\n";
+        assert_eq!(
+            part1(doubling.as_bytes(), &params),
+            Err("path count overflows i64".to_string())
+        );
+    }
+
+    #[test]
+    fn test_shrink_edges() {
+        // Fails while svr -> fft and fft -> eee are both present
+        let fails = |s: &[u8]| {
+            let edge = |graph: &Graph, src: &[u8], dest: &[u8]| {
+                graph.get(src).is_some_and(|dests| dests.contains(&dest))
+            };
+            parse_graph(s)
+                .is_ok_and(|graph| edge(&graph, b"svr", b"fft") && edge(&graph, b"fft", b"eee"))
+        };
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(minimize(input, UNITS, fails), b"svr: fft\nfft: eee\n");
+    }
+
+    #[test]
+    fn fuzz_parse_graph() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(|input| parse_graph::<FxBuildHasher>(input).map(|graph| graph.len()));
+    }
 }
@@ -0,0 +1,12 @@
+use common::params::Params;
+use common::scan;
+use day11::{run, DEFAULTS};
+
+fn main() {
+    let params = Params::load("day11", DEFAULTS).expect("Invalid parameters");
+    let input = scan::read_input("input.txt").expect("Failed to read input.txt");
+    let (part1, part2) = run(&input, &params).expect("Invalid input");
+
+    println!("Part 1: {part1}");
+    println!("Part 2: {part2}");
+}
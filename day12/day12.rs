@@ -2,12 +2,12 @@
 // Polyomino packing problem with rotation and reflection
 // Run with: cargo run
 
-use std::collections::HashSet;
-use std::fs;
-use std::path::Path;
+use algo::orientations::{self, Shape};
+use common::minimize::{keeper, Units};
+use common::scan;
+use common::svg::Svg;
 
-type Point = (i32, i32);
-type Shape = Vec<Point>;
+pub const SOLVER_VERSION: &str = "2";
 
 #[derive(Debug)]
 struct Region {
@@ -19,106 +19,77 @@ struct Region {
 mod shape {
     use super::*;
 
-    pub fn parse(lines: &[&str]) -> Shape {
+    pub fn parse(lines: &[&[u8]]) -> Shape {
         let mut points = Vec::new();
         for (row, line) in lines.iter().enumerate() {
-            for (col, ch) in line.chars().enumerate() {
-                if ch == '#' {
+            for (col, &cell) in line.iter().enumerate() {
+                if cell == b'#' {
                     points.push((row as i32, col as i32));
                 }
             }
         }
         points
     }
+}
 
-    pub fn normalize(shape: &Shape) -> Shape {
-        let min_row = shape.iter().map(|p| p.0).min().unwrap_or(0);
-        let min_col = shape.iter().map(|p| p.1).min().unwrap_or(0);
-        let mut normalized: Shape = shape
-            .iter()
-            .map(|p| (p.0 - min_row, p.1 - min_col))
-            .collect();
-        normalized.sort();
-        normalized
-    }
-
-    fn rotate90(shape: &Shape) -> Shape {
-        shape.iter().map(|p| (p.1, -p.0)).collect()
-    }
-
-    fn reflect(shape: &Shape) -> Shape {
-        shape.iter().map(|p| (p.0, -p.1)).collect()
-    }
+mod parser {
+    use super::*;
 
-    // Using iterator to generate rotations lazily - avoiding infinite collection issue!
-    fn rotations(shape: &Shape) -> impl Iterator<Item = Shape> {
-        let mut current = shape.clone();
-        (0..4).map(move |i| {
-            if i > 0 {
-                current = rotate90(&current);
-            }
-            current.clone()
+    fn region(line: &[u8], num_shapes: usize) -> Result<Region, String> {
+        let invalid = || format!("expected WxH: counts, got {:?}", scan::show(line));
+        let (dims, counts) = scan::split_once(line, b':').ok_or_else(invalid)?;
+        let (width, height) = scan::split_once(dims, b'x').ok_or_else(invalid)?;
+        let counts: Vec<usize> = counts
+            .strip_prefix(b" ")
+            .ok_or_else(invalid)?
+            .split(|&b| b == b' ')
+            .map(|s| scan::parse(s).ok_or_else(invalid))
+            .collect::<Result<_, _>>()?;
+        if counts.len() > num_shapes {
+            return Err(format!(
+                "{} shape counts for {} shapes: {:?}",
+                counts.len(),
+                num_shapes,
+                scan::show(line)
+            ));
+        }
+        Ok(Region {
+            width: scan::parse(width).ok_or_else(invalid)?,
+            height: scan::parse(height).ok_or_else(invalid)?,
+            shape_counts: counts,
         })
     }
 
-    pub fn all_orientations(shape: &Shape) -> Vec<Shape> {
-        let mut seen = HashSet::new();
-        let mut orientations = Vec::new();
-
-        // 4 rotations of original
-        for rotated in rotations(shape) {
-            let normalized = normalize(&rotated);
-            let key: Vec<_> = normalized.iter().copied().collect();
-            if seen.insert(key) {
-                orientations.push(normalized);
+    /// Shapes (an `N:` line, then rows of `#` and `.`) and regions (lines of
+    /// `WxH: counts`); any other line is an error
+    pub fn parse(content: &[u8]) -> Result<(Vec<Shape>, Vec<Region>), String> {
+        let mut shapes = Vec::new();
+        let mut regions = Vec::new();
+
+        for block in scan::blocks(content) {
+            let header = block[0];
+            let index = header.strip_suffix(b":").and_then(scan::parse::<usize>);
+            if index.is_none() {
+                for line in block {
+                    regions.push(region(line, shapes.len())?);
+                }
+                continue;
             }
-        }
 
-        // 4 rotations of reflected
-        let reflected = reflect(shape);
-        for rotated in rotations(&reflected) {
-            let normalized = normalize(&rotated);
-            let key: Vec<_> = normalized.iter().copied().collect();
-            if seen.insert(key) {
-                orientations.push(normalized);
+            if let Some(row) = block[1..]
+                .iter()
+                .find(|row| row.iter().any(|&cell| cell != b'#' && cell != b'.'))
+            {
+                return Err(format!("expected a row of # and .: {:?}", scan::show(row)));
+            }
+            let shape = shape::parse(&block[1..]);
+            if shape.is_empty() {
+                return Err(format!("shape {:?} has no cells", scan::show(header)));
             }
+            shapes.push(shape);
         }
 
-        orientations
-    }
-}
-
-mod parser {
-    use super::*;
-
-    pub fn parse(content: &str) -> (Vec<Shape>, Vec<Region>) {
-        let sections: Vec<&str> = content.split("\n\n").collect();
-
-        let shapes: Vec<Shape> = sections
-            .iter()
-            .filter(|s| s.contains(':') && !s.contains('x'))
-            .map(|section| {
-                let lines: Vec<&str> = section.lines().skip(1).filter(|l| !l.is_empty()).collect();
-                shape::parse(&lines)
-            })
-            .collect();
-
-        let regions: Vec<Region> = content
-            .lines()
-            .filter(|line| line.contains('x') && line.contains(':'))
-            .map(|line| {
-                let parts: Vec<&str> = line.split(": ").collect();
-                let dims: Vec<&str> = parts[0].split('x').collect();
-                let counts: Vec<usize> = parts[1].split(' ').map(|s| s.parse().unwrap()).collect();
-                Region {
-                    width: dims[0].parse().unwrap(),
-                    height: dims[1].parse().unwrap(),
-                    shape_counts: counts,
-                }
-            })
-            .collect();
-
-        (shapes, regions)
+        Ok((shapes, regions))
     }
 }
 
@@ -168,7 +139,7 @@ mod solver {
             .shape_counts
             .iter()
             .enumerate()
-            .flat_map(|(idx, &count)| std::iter::repeat(idx).take(count))
+            .flat_map(|(idx, &count)| std::iter::repeat_n(idx, count))
             .collect();
 
         // Early exit: check if total cells needed exceeds grid size
@@ -292,28 +263,212 @@ mod solver {
     }
 }
 
-fn main() {
-    // Find input file
-    let input_path = if Path::new("input.txt").exists() {
-        "input.txt".to_string()
-    } else if Path::new("day12/input.txt").exists() {
-        "day12/input.txt".to_string()
-    } else {
-        panic!("Could not find input.txt");
-    };
-
-    let content = fs::read_to_string(&input_path).expect("Failed to read input file");
-    let (shapes, regions) = parser::parse(&content);
+/// The only answer for `input`: how many regions fit all their presents
+pub fn part1(input: &[u8]) -> Result<usize, String> {
+    let (shapes, regions) = parser::parse(input)?;
 
     // Precompute all orientations for each shape
     let all_orientations: Vec<Vec<Shape>> =
-        shapes.iter().map(|s| shape::all_orientations(s)).collect();
+        shapes.iter().map(orientations::all_orientations).collect();
 
     // Count regions where all shapes can fit
-    let part1 = regions
+    Ok(regions
         .iter()
         .filter(|region| solver::can_fit_all(region, &all_orientations))
-        .count();
+        .count())
+}
+
+/// Day 12 has no second puzzle, so `run` is just `part1`
+pub fn run(input: &[u8]) -> Result<usize, String> {
+    part1(input)
+}
+
+/// The input as SVG: the present shapes along the top, then one square per
+/// region, green where its presents fit and grey where they don't, with
+/// the region's line as its tooltip
+pub fn svg(input: &[u8]) -> Result<String, String> {
+    const PER_ROW: usize = 40;
 
-    println!("Part 1: {}", part1);
+    let (shapes, regions) = parser::parse(input)?;
+    let all_orientations: Vec<Vec<Shape>> =
+        shapes.iter().map(orientations::all_orientations).collect();
+    let extent = |shape: &Shape, axis: fn(&(i32, i32)) -> i32| {
+        shape.iter().map(axis).max().unwrap_or(0) as f64 + 1.0
+    };
+
+    let shapes_width: f64 = shapes.iter().map(|s| extent(s, |p| p.1) + 1.0).sum();
+    let shapes_height = shapes
+        .iter()
+        .map(|s| extent(s, |p| p.0))
+        .fold(0.0, f64::max);
+    let region_rows = regions.len().div_ceil(PER_ROW) as f64;
+    let mut svg = Svg::new(
+        shapes_width.max(PER_ROW as f64),
+        shapes_height + 1.0 + region_rows,
+    );
+
+    let mut left = 0.0;
+    for shape in &shapes {
+        for &(row, col) in shape {
+            svg.rect(left + col as f64, row as f64, 1.0, 1.0, "#8e44ad");
+        }
+        left += extent(shape, |p| p.1) + 1.0;
+    }
+
+    let mut fits = 0;
+    for (i, region) in regions.iter().enumerate() {
+        let fit = solver::can_fit_all(region, &all_orientations);
+        fits += fit as usize;
+        let counts: Vec<String> = region.shape_counts.iter().map(usize::to_string).collect();
+        let title = format!(
+            "{}x{}: {} ({})",
+            region.width,
+            region.height,
+            counts.join(" "),
+            if fit { "fits" } else { "doesn't fit" }
+        );
+        let (x, y) = (
+            (i % PER_ROW) as f64,
+            shapes_height + 1.0 + (i / PER_ROW) as f64,
+        );
+        let fill = if fit { "#27ae60" } else { "#bdc3c7" };
+        svg.rect_titled((x, y, 0.8, 0.8), fill, &title);
+    }
+    svg.title(&format!(
+        "{} shapes; {fits} of {} regions fit",
+        shapes.len(),
+        regions.len()
+    ));
+    Ok(svg.finish())
+}
+
+/// Just the parser, for telling which day an input belongs to
+pub fn validate(input: &[u8]) -> Result<(), String> {
+    parser::parse(input).map(drop)
+}
+
+/// Minimizer units: whole blank-line separated blocks, i.e. single shapes
+/// or the run of regions after them
+pub struct Blocks;
+
+/// Minimizer units: single lines, keeping the blank lines between blocks
+/// so that shapes stay apart
+pub struct Rows;
+
+fn write_blocks<'a>(blocks: impl Iterator<Item = Vec<&'a [u8]>>) -> Vec<u8> {
+    let blocks: Vec<Vec<u8>> = blocks
+        .filter(|block| !block.is_empty())
+        .map(|block| {
+            block
+                .iter()
+                .flat_map(|line| [*line, b"\n"])
+                .flatten()
+                .copied()
+                .collect()
+        })
+        .collect();
+    blocks.join(&b'\n')
+}
+
+impl Units for Blocks {
+    fn count(&self, input: &[u8]) -> usize {
+        scan::blocks(input).len()
+    }
+
+    fn keep(&self, input: &[u8], kept: &[usize]) -> Vec<u8> {
+        let mut keep = keeper(kept);
+        write_blocks(scan::blocks(input).into_iter().filter(|_| keep()))
+    }
+}
+
+impl Units for Rows {
+    fn count(&self, input: &[u8]) -> usize {
+        scan::blocks(input).iter().map(Vec::len).sum()
+    }
+
+    fn keep(&self, input: &[u8], kept: &[usize]) -> Vec<u8> {
+        let mut keep = keeper(kept);
+        write_blocks(
+            scan::blocks(input)
+                .into_iter()
+                .map(|block| block.into_iter().filter(|_| keep()).collect()),
+        )
+    }
+}
+
+/// This day's minimizer passes
+pub const UNITS: &[&dyn Units] = &[&Blocks, &Rows];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::fuzz::Fuzzer;
+    use common::minimize::minimize;
+    use common::snapshot::assert_snapshot;
+
+    #[test]
+    fn test_example() {
+        assert_eq!(part1(include_bytes!("test_input.txt")), Ok(3));
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = svg(include_bytes!("test_input.txt")).unwrap();
+        assert!(
+            svg.contains("<title>6 shapes; 3 of 3 regions fit</title>"),
+            "{svg}"
+        );
+        assert!(svg.contains("<title>4x4: 0 0 0 0 2 0 (fits)</title>"));
+        assert_eq!(svg.matches("#27ae60").count(), 3);
+        assert_snapshot(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/test_input.svg"),
+            &svg,
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            part1(b"0:\n#\n\n3x3: 1 1\n"),
+            Err("2 shape counts for 1 shapes: \"3x3: 1 1\"".to_string())
+        );
+        assert_eq!(
+            part1(b"0:\n...\n\n3x3: 1\n"),
+            Err("shape \"0:\" has no cells".to_string())
+        );
+        assert_eq!(part1(b"0:\n#\n\n3x3: 2\n"), Ok(1));
+
+        // Every line has to parse, not just the ones that look like regions
+        assert_eq!(
+            part1(b"0:\n#\n\n3x3: 2\n20777,62942,19723\n"),
+            Err("expected WxH: counts, got \"20777,62942,19723\"".to_string())
+        );
+        assert_eq!(
+            part1(b"0:\n#x\n\n3x3: 2\n"),
+            Err("expected a row of # and .: \"#x\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_shrink() {
+        // Every region counts all six shapes, so the shapes stay, cut down
+        // to a row each, with the blank lines that keep them apart
+        let input = include_bytes!("test_input.txt");
+        assert_eq!(
+            minimize(input, UNITS, |s| part1(s) == Ok(1)),
+            b"0:\n##.\n\n1:\n..#\n\n2:\n#.#\n\n3:\n###\n\n4:\n#..\n\n5:\n##.\n\n12x5: 1 0 1 0 3 2\n"
+        );
+        assert_eq!(
+            minimize(b"0:\n#\n\n1:\n##\n\n1x1: 1\n", UNITS, |s| part1(s) == Ok(1)),
+            b"0:\n#\n\n1x1: 1\n"
+        );
+    }
+
+    #[test]
+    fn fuzz_parse() {
+        Fuzzer::new(concat!(env!("CARGO_MANIFEST_DIR"), "/fuzz"))
+            .seed(include_str!("test_input.txt"))
+            .units(UNITS)
+            .run(parser::parse);
+    }
 }
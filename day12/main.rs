@@ -0,0 +1,19 @@
+use common::scan;
+use day12::run;
+use std::path::Path;
+
+fn main() {
+    // Find input file
+    let input_path = if Path::new("input.txt").exists() {
+        "input.txt".to_string()
+    } else if Path::new("day12/input.txt").exists() {
+        "day12/input.txt".to_string()
+    } else {
+        panic!("Could not find input.txt");
+    };
+
+    let content = scan::read_input(&input_path).expect("Failed to read input file");
+    let part1 = run(&content).expect("Invalid input");
+
+    println!("Part 1: {}", part1);
+}
@@ -0,0 +1,36 @@
+/* Generated from ffi/lib.rs by `UPDATE_HEADER=1 cargo test -p ffi`; do not edit. */
+
+#ifndef AOC_H
+#define AOC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Bytes out_buf must have room for, including the terminating NUL */
+#define AOC_ANSWER_CAPACITY 64
+
+/* Return codes */
+#define AOC_OK 0 /* Answer written to out_buf */
+#define AOC_ERR_NULL (-1) /* input or out_buf is NULL */
+#define AOC_ERR_DAY (-2) /* No such day */
+#define AOC_ERR_PART (-3) /* No such part for this day */
+#define AOC_ERR_INPUT (-4) /* Input rejected; out_buf holds the (truncated) reason */
+#define AOC_ERR_PANIC (-5) /* Solver panicked, usually on input the parser let through */
+
+/*
+ * Solves `part` (1 or 2; day 12 has only part 1) of `day` (1-12) for the
+ * `len` bytes at `input`, using each day's default parameters. On AOC_OK,
+ * out_buf holds the answer as a NUL-terminated decimal string.
+ */
+int32_t aoc_solve(uint32_t day, uint32_t part, const uint8_t *input, size_t len,
+                  char *out_buf);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AOC_H */
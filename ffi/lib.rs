@@ -0,0 +1,226 @@
+// C ABI over every day's solver
+//
+// Builds a shared library (libaoc) so the ports in other languages can
+// cross-check their answers against the Rust ones. Days are looked up in
+// the year2025 table, which calls each day's `part1`/`part2` with the default
+// parameters, so only the requested part is computed. The C
+// declarations in aoc.h are generated from this file by `header`; regenerate
+// them with `UPDATE_HEADER=1 cargo test -p ffi`.
+
+use std::ffi::c_char;
+use std::{ptr, slice};
+
+/// Bytes `out_buf` must have room for, including the terminating NUL.
+/// Every answer is an integer, so any 128-bit value fits.
+pub const AOC_ANSWER_CAPACITY: usize = 64;
+
+pub const AOC_OK: i32 = 0;
+pub const AOC_ERR_NULL: i32 = -1;
+pub const AOC_ERR_DAY: i32 = -2;
+pub const AOC_ERR_PART: i32 = -3;
+pub const AOC_ERR_INPUT: i32 = -4;
+pub const AOC_ERR_PANIC: i32 = -5;
+
+const CODES: &[(&str, i32, &str)] = &[
+    ("AOC_OK", AOC_OK, "Answer written to out_buf"),
+    ("AOC_ERR_NULL", AOC_ERR_NULL, "input or out_buf is NULL"),
+    ("AOC_ERR_DAY", AOC_ERR_DAY, "No such day"),
+    ("AOC_ERR_PART", AOC_ERR_PART, "No such part for this day"),
+    (
+        "AOC_ERR_INPUT",
+        AOC_ERR_INPUT,
+        "Input rejected; out_buf holds the (truncated) reason",
+    ),
+    (
+        "AOC_ERR_PANIC",
+        AOC_ERR_PANIC,
+        "Solver panicked, usually on input the parser let through",
+    ),
+];
+
+pub use common::solution::SolveError;
+
+fn code(err: &SolveError) -> i32 {
+    match err {
+        SolveError::UnknownDay => AOC_ERR_DAY,
+        SolveError::UnknownPart => AOC_ERR_PART,
+        SolveError::Input(_) => AOC_ERR_INPUT,
+        SolveError::Panic => AOC_ERR_PANIC,
+    }
+}
+
+/// Safe core of `aoc_solve`; computes just the requested part
+pub fn solve(day: u32, part: u32, input: &[u8]) -> Result<String, SolveError> {
+    year2025::YEAR.solve(day, part, input)
+}
+
+/// Solves `part` of `day` for the `len` bytes at `input` and writes the
+/// answer to `out_buf` as a NUL-terminated decimal string.
+///
+/// # Safety
+///
+/// `input` must be valid for reads of `len` bytes and `out_buf` for writes
+/// of `AOC_ANSWER_CAPACITY` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u32,
+    part: u32,
+    input: *const u8,
+    len: usize,
+    out_buf: *mut c_char,
+) -> i32 {
+    if input.is_null() || out_buf.is_null() {
+        return AOC_ERR_NULL;
+    }
+    let input = slice::from_raw_parts(input, len);
+
+    let (code, text) = match solve(day, part, input) {
+        Ok(answer) => (AOC_OK, answer),
+        Err(err) => {
+            let text = match &err {
+                SolveError::Input(reason) => reason.clone(),
+                _ => String::new(),
+            };
+            (code(&err), text)
+        }
+    };
+
+    // Cut at a char boundary so out_buf always holds valid UTF-8
+    let mut end = text.len().min(AOC_ANSWER_CAPACITY - 1);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    ptr::copy_nonoverlapping(text.as_ptr(), out_buf.cast::<u8>(), end);
+    *out_buf.add(end) = 0;
+
+    code
+}
+
+/// The C declarations for this library, as checked in at ffi/aoc.h
+pub fn header() -> String {
+    let codes: String = CODES
+        .iter()
+        .map(|(name, code, meaning)| match code {
+            0.. => format!("#define {name} {code} /* {meaning} */\n"),
+            _ => format!("#define {name} ({code}) /* {meaning} */\n"),
+        })
+        .collect();
+
+    format!(
+        r#"/* Generated from ffi/lib.rs by `UPDATE_HEADER=1 cargo test -p ffi`; do not edit. */
+
+#ifndef AOC_H
+#define AOC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {{
+#endif
+
+/* Bytes out_buf must have room for, including the terminating NUL */
+#define AOC_ANSWER_CAPACITY {AOC_ANSWER_CAPACITY}
+
+/* Return codes */
+{codes}
+/*
+ * Solves `part` (1 or 2; day 12 has only part 1) of `day` (1-12) for the
+ * `len` bytes at `input`, using each day's default parameters. On AOC_OK,
+ * out_buf holds the answer as a NUL-terminated decimal string.
+ */
+int32_t aoc_solve(uint32_t day, uint32_t part, const uint8_t *input, size_t len,
+                  char *out_buf);
+
+#ifdef __cplusplus
+}}
+#endif
+
+#endif /* AOC_H */
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUTS: [&[u8]; 12] = [
+        include_bytes!("../day01/test_input.txt"),
+        include_bytes!("../day02/test_input.txt"),
+        include_bytes!("../day03/test_input.txt"),
+        include_bytes!("../day04/test_input.txt"),
+        include_bytes!("../day05/test_input.txt"),
+        include_bytes!("../day06/test_input.txt"),
+        include_bytes!("../day07/test_input.txt"),
+        include_bytes!("../day08/test_input.txt"),
+        include_bytes!("../day09/test_input.txt"),
+        include_bytes!("../day10/test_input.txt"),
+        include_bytes!("../day11/test_input.txt"),
+        include_bytes!("../day12/test_input.txt"),
+    ];
+
+    #[test]
+    fn test_answers() {
+        let answers: [[&str; 2]; 12] = [
+            ["0", "3"],
+            ["351085", "1715506238"],
+            ["198", "1843006909854"],
+            ["13", "43"],
+            ["3", "14"],
+            ["65581894710", "231156135405"],
+            ["13", "18"],
+            ["20", "2101473568"],
+            ["50", "24"],
+            ["8", "374"],
+            ["3", "1"],
+            ["3", ""],
+        ];
+        for ((input, answers), day) in TEST_INPUTS.iter().zip(answers).zip(1..) {
+            for (answer, part) in answers.into_iter().zip(1..) {
+                let expected = match answer {
+                    "" => Err(SolveError::UnknownPart),
+                    answer => Ok(answer.to_string()),
+                };
+                assert_eq!(solve(day, part, input), expected, "day {day} part {part}");
+            }
+        }
+        assert_eq!(solve(13, 1, b""), Err(SolveError::UnknownDay));
+    }
+
+    #[test]
+    fn test_wrong_input_is_rejected() {
+        assert!(matches!(solve(9, 1, b""), Err(SolveError::Input(_))));
+        assert!(matches!(solve(9, 2, b"1,1\n"), Err(SolveError::Input(_))));
+        // A cycle would recurse until the stack overflows, which no
+        // catch_unwind can stop
+        let cycle = b"you: a\na: you out\nsvr: out\n";
+        assert_eq!(
+            solve(11, 1, cycle),
+            Err(SolveError::Input("cycle through you".to_string()))
+        );
+
+        // Every solver on every other day's input: some happen to parse, but
+        // none may panic
+        for day in 1..=12 {
+            for part in 1..=2 {
+                for (input, other) in TEST_INPUTS.iter().zip(1..) {
+                    if other != day {
+                        let answer = solve(day, part, input);
+                        assert_ne!(
+                            answer,
+                            Err(SolveError::Panic),
+                            "day {day} part {part} on day {other}"
+                        );
+                    }
+                }
+                let answer = solve(day, part, b"");
+                assert_ne!(
+                    answer,
+                    Err(SolveError::Panic),
+                    "day {day} part {part} on no input"
+                );
+            }
+        }
+    }
+}
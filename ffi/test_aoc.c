@@ -0,0 +1,88 @@
+/*
+ * Exercises every day through libaoc: prints each part's answer for the
+ * day's <input file> (test_input.txt unless given) as "dayNN part P:
+ * <answer>" (or "error <code>"), then checks the error codes. Exits
+ * non-zero if a check fails.
+ *
+ * Usage: test_aoc <repo root> [input file]
+ * Build: cc -I ffi ffi/test_aoc.c -L target/debug -laoc -o test_aoc
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "aoc.h"
+
+static int failures = 0;
+
+static void expect(const char *what, int32_t got, int32_t want) {
+    if (got != want) {
+        fprintf(stderr, "FAIL %s: got %d, want %d\n", what, got, want);
+        failures++;
+    }
+}
+
+static uint8_t *read_file(const char *path, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    if (!file) {
+        return NULL;
+    }
+    fseek(file, 0, SEEK_END);
+    long size = ftell(file);
+    fseek(file, 0, SEEK_SET);
+
+    uint8_t *data = malloc(size > 0 ? (size_t)size : 1);
+    *len = fread(data, 1, (size_t)size, file);
+    fclose(file);
+    return data;
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2 && argc != 3) {
+        fprintf(stderr, "usage: %s <repo root> [input file]\n", argv[0]);
+        return 2;
+    }
+    const char *name = argc == 3 ? argv[2] : "test_input.txt";
+
+    char path[4096];
+    char out[AOC_ANSWER_CAPACITY];
+
+    for (uint32_t day = 1; day <= 12; day++) {
+        snprintf(path, sizeof path, "%s/day%02u/%s", argv[1], day, name);
+        size_t len = 0;
+        uint8_t *input = read_file(path, &len);
+        if (!input) {
+            fprintf(stderr, "FAIL cannot read %s\n", path);
+            failures++;
+            continue;
+        }
+
+        for (uint32_t part = 1; part <= 2; part++) {
+            int32_t code = aoc_solve(day, part, input, len, out);
+            if (code == AOC_OK) {
+                printf("day%02u part %u: %s\n", day, part, out);
+            } else {
+                printf("day%02u part %u: error %d\n", day, part, code);
+            }
+        }
+        free(input);
+    }
+
+    const char *bad = "X1\n";
+    const uint8_t *bytes = (const uint8_t *)bad;
+    size_t bad_len = strlen(bad);
+
+    expect("unknown day", aoc_solve(13, 1, bytes, bad_len, out), AOC_ERR_DAY);
+    expect("unknown part", aoc_solve(1, 3, (const uint8_t *)"R1\n", 3, out), AOC_ERR_PART);
+    expect("null input", aoc_solve(1, 1, NULL, 0, out), AOC_ERR_NULL);
+    expect("null out_buf", aoc_solve(1, 1, bytes, bad_len, NULL), AOC_ERR_NULL);
+    expect("bad input", aoc_solve(1, 1, bytes, bad_len, out), AOC_ERR_INPUT);
+    if (out[0] == '\0') {
+        fprintf(stderr, "FAIL bad input: no reason in out_buf\n");
+        failures++;
+    }
+    expect("empty input", aoc_solve(9, 1, bytes, 0, out), AOC_ERR_INPUT);
+
+    return failures == 0 ? 0 : 1;
+}
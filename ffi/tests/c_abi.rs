@@ -0,0 +1,145 @@
+// Checks the C ABI end to end: the checked-in header must match the
+// generated one, and the C test program, linked against libaoc, must print
+// the known answers for each day's test_input.txt, so a bug in the ABI
+// can't confirm itself. The same check on the full input.txt files takes
+// minutes in a debug build, so it only runs when asked for:
+//
+//     cargo test --release -p ffi -- --ignored
+//
+// Without a C compiler these tests are skipped; the answers themselves are
+// also checked in Rust by ffi/lib.rs's tests.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
+
+/// Each day's answers for its test_input.txt, worked out independently of
+/// the solvers
+const TEST_ANSWERS: &[[&str; 2]] = &[
+    ["0", "3"],
+    ["351085", "1715506238"],
+    ["198", "1843006909854"],
+    ["13", "43"],
+    ["3", "14"],
+    ["65581894710", "231156135405"],
+    ["13", "18"],
+    ["20", "2101473568"],
+    ["50", "24"],
+    ["8", "374"],
+    ["3", "1"],
+    ["3", "error -3"],
+];
+
+/// Each day's answers for its input.txt, as printed by `cargo run -p dayNN`
+const INPUT_ANSWERS: &[[&str; 2]] = &[
+    ["1092", "6616"],
+    ["23534117921", "31755323497"],
+    ["17113", "169709990062889"],
+    ["13", "43"],
+    ["3", "14"],
+    ["5733696195703", "10951882745757"],
+    ["1635", "58097428661390"],
+    ["102816", "100011612"],
+    ["4749672288", "1479665889"],
+    ["550", "20042"],
+    ["724", "473930047491888"],
+    ["526", "error -3"],
+];
+
+/// target/<profile>/deps, where `cargo test` builds libaoc next to this
+/// test; it is only copied up to target/<profile> by `cargo build`
+fn library_dir() -> PathBuf {
+    let exe = env::current_exe().expect("test executable path");
+    exe.parent()
+        .expect("test executable under target/<profile>/deps")
+        .to_path_buf()
+}
+
+#[test]
+fn test_header_is_current() {
+    let path = Path::new(MANIFEST_DIR).join("aoc.h");
+    let generated = aoc::header();
+
+    if env::var_os("UPDATE_HEADER").is_some() {
+        fs::write(&path, &generated).expect("write aoc.h");
+    }
+    let checked_in = fs::read_to_string(&path).unwrap_or_default();
+    assert_eq!(
+        checked_in, generated,
+        "ffi/aoc.h is stale; regenerate it with UPDATE_HEADER=1 cargo test -p ffi"
+    );
+}
+
+/// Builds test_aoc.c against libaoc, or `None` if there is no C compiler
+fn build_test_program() -> Option<PathBuf> {
+    let lib_dir = library_dir();
+    let exe = lib_dir.join("test_aoc");
+    let cc = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+
+    let compiled = Command::new(&cc)
+        .arg("-std=c99")
+        .arg("-I")
+        .arg(MANIFEST_DIR)
+        .arg(Path::new(MANIFEST_DIR).join("test_aoc.c"))
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg("-laoc")
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-o")
+        .arg(&exe)
+        .status();
+    match compiled {
+        Ok(status) => {
+            assert!(status.success(), "{cc} failed to build test_aoc.c");
+            Some(exe)
+        }
+        Err(err) => {
+            eprintln!("skipping: no C compiler ({cc}: {err})");
+            None
+        }
+    }
+}
+
+/// Runs test_aoc on each day's `file` and compares what it prints
+fn check_c_program(file: &str, answers: &[[&str; 2]]) {
+    let Some(exe) = build_test_program() else {
+        return;
+    };
+    let root = Path::new(MANIFEST_DIR).parent().unwrap();
+    let output = Command::new(&exe)
+        .arg(root)
+        .arg(file)
+        .output()
+        .expect("run test_aoc");
+    assert!(
+        output.status.success(),
+        "test_aoc failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let expected: String = answers
+        .iter()
+        .zip(1..)
+        .flat_map(|(answers, day)| {
+            answers
+                .iter()
+                .zip(1..)
+                .map(move |(answer, part)| format!("day{day:02} part {part}: {answer}\n"))
+        })
+        .collect();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected);
+}
+
+#[test]
+fn test_c_program_prints_test_answers() {
+    check_c_program("test_input.txt", TEST_ANSWERS);
+}
+
+#[test]
+#[ignore = "minutes in a debug build; run with --release -- --ignored"]
+fn test_c_program_prints_input_answers() {
+    check_c_program("input.txt", INPUT_ANSWERS);
+}
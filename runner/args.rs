@@ -0,0 +1,121 @@
+// Command line arguments
+//
+// Hand-parsed: each command declares which `--name value` (or `--name=value`)
+// options and `--flag` switches it takes; anything else starting with `--`
+// is an error, and the remaining words are positional.
+
+use std::str::FromStr;
+
+/// The options one command accepts
+pub struct Spec {
+    pub values: &'static [&'static str],
+    pub flags: &'static [&'static str],
+}
+
+#[derive(Debug, Default)]
+pub struct Args {
+    values: Vec<(&'static str, String)>,
+    flags: Vec<&'static str>,
+    pub positional: Vec<String>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>, spec: &Spec) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let Some(option) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg);
+                continue;
+            };
+            let (name, inline) = match option.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (option, None),
+            };
+
+            if let Some(&name) = spec.values.iter().find(|&&known| known == name) {
+                let value = inline
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("--{name} needs a value"))?;
+                parsed.values.push((name, value));
+            } else if let Some(&name) = spec.flags.iter().find(|&&known| known == name) {
+                if inline.is_some() {
+                    return Err(format!("--{name} takes no value"));
+                }
+                parsed.flags.push(name);
+            } else {
+                return Err(format!("unknown option --{name}"));
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// The last value given for `name`
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rfind(|(known, _)| *known == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Every value given for `name`, for repeatable options like `--param`
+    pub fn values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.values
+            .iter()
+            .filter(move |(known, _)| *known == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn number<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.value(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid --{name}: {value}"))
+            })
+            .transpose()
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: Spec = Spec {
+        values: &["day", "param"],
+        flags: &["all"],
+    };
+
+    fn parse(args: &str) -> Result<Args, String> {
+        Args::parse(args.split_whitespace().map(String::from), &SPEC)
+    }
+
+    #[test]
+    fn test_parse() {
+        let args = parse("--day 7 file --param a=1 --param=b=2 --all").unwrap();
+        assert_eq!(args.number::<u32>("day"), Ok(Some(7)));
+        assert_eq!(args.values("param").collect::<Vec<_>>(), ["a=1", "b=2"]);
+        assert!(args.flag("all"));
+        assert_eq!(args.positional, ["file"]);
+
+        let args = parse("--day=x").unwrap();
+        assert_eq!(
+            args.number::<u32>("day"),
+            Err("invalid --day: x".to_string())
+        );
+        assert_eq!(args.value("param"), None);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse("--day").unwrap_err(), "--day needs a value");
+        assert_eq!(parse("--all=1").unwrap_err(), "--all takes no value");
+        assert_eq!(parse("--year 2025").unwrap_err(), "unknown option --year");
+    }
+}
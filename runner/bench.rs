@@ -0,0 +1,464 @@
+// `aoc bench`: repeated timings, a history of them, and regression checks
+//
+// Every `--record`ed run appends one line per part to .aoc/bench.tsv:
+// commit, year, day, part, input digest, and the run times in nanoseconds.
+// Nothing in the history is ever rewritten. `--compare` takes the latest
+// record with the same year, day, part and input from another commit (or
+// from `--baseline`) and calls a part slower when a one-sided Mann-Whitney
+// U test says so at p < 0.005 and its median is at least 5% up. Run times
+// are rarely normal, and a rank test shrugs off the odd descheduled run.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Instant;
+
+use common::scan;
+
+use crate::args::{Args, Spec};
+use crate::run::{input_path, Selection};
+
+pub const SPEC: Spec = Spec {
+    values: &[
+        "year", "day", "part", "input", "runs", "history", "commit", "baseline", "data-dir",
+    ],
+    flags: &["all", "record", "compare"],
+};
+
+const DEFAULT_RUNS: usize = 10;
+
+/// One-sided z for p < 0.005
+const CRITICAL_Z: f64 = 2.576;
+
+/// Smallest change in the median worth reporting
+const MIN_CHANGE: f64 = 0.05;
+
+/// One part's run times at one commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub commit: String,
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub input: u64,
+    /// Nanoseconds per run
+    pub samples: Vec<u64>,
+}
+
+impl Record {
+    fn parse(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [commit, year, day, part, input, samples] = fields[..] else {
+            return None;
+        };
+        let samples = samples
+            .split(',')
+            .map(|sample| sample.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        Some(Self {
+            commit: commit.to_string(),
+            year: year.parse().ok()?,
+            day: day.parse().ok()?,
+            part: part.parse().ok()?,
+            input: u64::from_str_radix(input, 16).ok()?,
+            samples,
+        })
+    }
+
+    fn line(&self) -> String {
+        let samples: Vec<String> = self.samples.iter().map(u64::to_string).collect();
+        format!(
+            "{}\t{}\t{}\t{}\t{:016x}\t{}\n",
+            self.commit,
+            self.year,
+            self.day,
+            self.part,
+            self.input,
+            samples.join(",")
+        )
+    }
+
+    /// Same year, day, part and input
+    fn same_key(&self, other: &Record) -> bool {
+        (self.year, self.day, self.part, self.input)
+            == (other.year, other.day, other.part, other.input)
+    }
+
+    pub fn median(&self) -> f64 {
+        median(&self.samples)
+    }
+}
+
+pub struct History {
+    path: PathBuf,
+    pub records: Vec<Record>,
+}
+
+impl History {
+    /// The history at `path`, empty if there is no file yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("{}: {err}", path.display())),
+        };
+        let records = text
+            .lines()
+            .zip(1..)
+            .map(|(line, number)| {
+                Record::parse(line)
+                    .ok_or_else(|| format!("{}:{number}: malformed bench record", path.display()))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { path, records })
+    }
+
+    pub fn append(&mut self, record: Record) -> Result<(), String> {
+        let append = || -> io::Result<()> {
+            if let Some(dir) = self.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?
+                .write_all(record.line().as_bytes())
+        };
+        append().map_err(|err| format!("{}: {err}", self.path.display()))?;
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// The latest record to compare `current` with: from `commit` if given
+    /// (a prefix is enough), otherwise from any other commit
+    pub fn baseline(&self, current: &Record, commit: Option<&str>) -> Option<&Record> {
+        self.records.iter().rev().find(|record| {
+            record.same_key(current)
+                && match commit {
+                    Some(commit) => record.commit.starts_with(commit),
+                    None => record.commit != current.commit,
+                }
+        })
+    }
+}
+
+pub fn median(samples: &[u64]) -> f64 {
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    match sorted.len() {
+        0 => 0.0,
+        len if len % 2 == 1 => sorted[len / 2] as f64,
+        len => (sorted[len / 2 - 1] + sorted[len / 2]) as f64 / 2.0,
+    }
+}
+
+/// Mann-Whitney U as a z score, with the normal approximation; positive
+/// when `new` tends to be larger than `old`
+pub fn mann_whitney_z(old: &[u64], new: &[u64]) -> f64 {
+    let (n1, n2) = (old.len() as f64, new.len() as f64);
+    let u: f64 = new
+        .iter()
+        .flat_map(|&b| old.iter().map(move |&a| (a, b)))
+        .map(|(a, b)| match b.cmp(&a) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        })
+        .sum();
+    let sd = (n1 * n2 * (n1 + n2 + 1.0) / 12.0).sqrt();
+    match sd {
+        0.0 => 0.0,
+        sd => (u - n1 * n2 / 2.0) / sd,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Slower,
+    Faster,
+    Unchanged,
+}
+
+impl Verdict {
+    fn label(self) -> &'static str {
+        match self {
+            Verdict::Slower => "**slower**",
+            Verdict::Faster => "faster",
+            Verdict::Unchanged => "unchanged",
+        }
+    }
+}
+
+pub fn verdict(baseline: &Record, current: &Record) -> Verdict {
+    let z = mann_whitney_z(&baseline.samples, &current.samples);
+    let change = current.median() / baseline.median() - 1.0;
+    if z > CRITICAL_Z && change >= MIN_CHANGE {
+        Verdict::Slower
+    } else if z < -CRITICAL_Z && change <= -MIN_CHANGE {
+        Verdict::Faster
+    } else {
+        Verdict::Unchanged
+    }
+}
+
+/// `git rev-parse --short HEAD` of the workspace, `-dirty` if it has
+/// uncommitted changes
+pub fn commit() -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(crate::workspace())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(commit) => match git(&["status", "--porcelain", "--untracked-files=no"]) {
+            Some(status) if status.is_empty() => commit,
+            _ => format!("{commit}-dirty"),
+        },
+        None => "unknown".to_string(),
+    }
+}
+
+pub(crate) fn nanos(nanos: f64) -> String {
+    format!("{:.2?}", std::time::Duration::from_nanos(nanos as u64))
+}
+
+/// One line of the summary table
+pub struct Row {
+    pub current: Record,
+    /// The baseline and the verdict against it, with `--compare`
+    pub compared: Option<Option<(Record, Verdict)>>,
+}
+
+/// The summary, as a markdown table
+pub fn render(rows: &[Row], out: &mut impl Write) -> io::Result<()> {
+    let compare = rows.iter().any(|row| row.compared.is_some());
+    match compare {
+        true => {
+            writeln!(
+                out,
+                "| Day | Part | Median | Min | Baseline | Baseline median | Change | Verdict |"
+            )?;
+            writeln!(out, "|---|---|---|---|---|---|---|---|")?;
+        }
+        false => {
+            writeln!(out, "| Day | Part | Median | Min | Runs |")?;
+            writeln!(out, "|---|---|---|---|---|")?;
+        }
+    }
+
+    for row in rows {
+        let current = &row.current;
+        let min = current.samples.iter().min().copied().unwrap_or(0);
+        write!(
+            out,
+            "| {} | {} | {} | {} |",
+            current.day,
+            current.part,
+            nanos(current.median()),
+            nanos(min as f64)
+        )?;
+        match &row.compared {
+            None => writeln!(out, " {} |", current.samples.len())?,
+            Some(None) => writeln!(out, " - | - | - | no baseline |")?,
+            Some(Some((baseline, verdict))) => {
+                let change = (current.median() / baseline.median() - 1.0) * 100.0;
+                writeln!(
+                    out,
+                    " {} | {} | {change:+.1}% | {} |",
+                    baseline.commit,
+                    nanos(baseline.median()),
+                    verdict.label()
+                )?
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `--history`, or .aoc/bench.tsv
+pub fn history_path(args: &Args) -> PathBuf {
+    args.value("history")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| crate::data_dir(args).join("bench.tsv"))
+}
+
+pub fn run(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let selection = Selection::from_args(args)?;
+    if selection.days.len() > 1 && args.value("input").is_some() {
+        return Err("--input needs a single --day".to_string());
+    }
+    let runs = args.number("runs")?.unwrap_or(DEFAULT_RUNS);
+    if runs == 0 {
+        return Err("--runs must be at least 1".to_string());
+    }
+    let commit = args.value("commit").map_or_else(commit, str::to_string);
+    let mut history = History::open(history_path(args))?;
+
+    let mut rows = Vec::new();
+    for solution in &selection.days {
+        let path = input_path(args, solution);
+        let input = scan::read_input(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+        let params = solution
+            .default_params()
+            .map_err(|err| format!("{}: {err}", solution.name()))?;
+
+        for part in selection.parts(solution) {
+            // The first run warms the caches and the allocator, and is dropped
+            let mut samples = Vec::with_capacity(runs);
+            for run in 0..=runs {
+                let start = Instant::now();
+                solution
+                    .run(part, &input, &params)
+                    .map_err(|err| format!("{} part {part}: {err}", solution.name()))?;
+                if run > 0 {
+                    samples.push(start.elapsed().as_nanos() as u64);
+                }
+            }
+            let current = Record {
+                commit: commit.clone(),
+                year: selection.year.year,
+                day: solution.day,
+                part,
+                input: crate::digest(&input),
+                samples,
+            };
+            let compared = args.flag("compare").then(|| {
+                history
+                    .baseline(&current, args.value("baseline"))
+                    .map(|baseline| (baseline.clone(), verdict(baseline, &current)))
+            });
+            rows.push(Row { current, compared });
+        }
+    }
+
+    if args.flag("record") {
+        for row in &rows {
+            history.append(row.current.clone())?;
+        }
+    }
+    render(&rows, out).map_err(|err| err.to_string())?;
+
+    let slower = rows
+        .iter()
+        .filter(|row| matches!(row.compared, Some(Some((_, Verdict::Slower)))))
+        .count();
+    match slower {
+        0 => Ok(()),
+        slower => Err(format!("{slower} part(s) significantly slower")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(commit: &str, samples: &[u64]) -> Record {
+        Record {
+            commit: commit.to_string(),
+            year: 2025,
+            day: 8,
+            part: 1,
+            input: 0xab,
+            samples: samples.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_statistics() {
+        assert_eq!(median(&[3, 1, 2]), 2.0);
+        assert_eq!(median(&[4, 1, 2, 3]), 2.5);
+
+        let old = [100, 101, 102, 99, 100, 103, 98, 100, 101, 100];
+        let slow = old.map(|t| t * 12 / 10);
+        assert!(mann_whitney_z(&old, &slow) > CRITICAL_Z);
+        assert!(mann_whitney_z(&slow, &old) < -CRITICAL_Z);
+        assert_eq!(mann_whitney_z(&old, &old), 0.0);
+
+        let base = record("a", &old);
+        assert_eq!(verdict(&base, &record("b", &slow)), Verdict::Slower);
+        assert_eq!(verdict(&record("b", &slow), &base), Verdict::Faster);
+        // Consistently but only 2% slower: not worth flagging
+        let slightly = old.map(|t| t * 102 / 100);
+        assert_eq!(verdict(&base, &record("b", &slightly)), Verdict::Unchanged);
+        // 20% slower in one outlier run out of ten: not significant
+        let mut outlier = old;
+        outlier[0] = 1000;
+        assert_eq!(verdict(&base, &record("b", &outlier)), Verdict::Unchanged);
+    }
+
+    #[test]
+    fn test_history() {
+        let path = std::env::temp_dir().join(format!("aoc-bench-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut history = History::open(&path).unwrap();
+        history.append(record("aaa111", &[5, 6])).unwrap();
+        history.append(record("bbb222", &[7, 8])).unwrap();
+        let history = History::open(&path).unwrap();
+        assert_eq!(history.records[1], record("bbb222", &[7, 8]));
+
+        let current = record("bbb222", &[9]);
+        assert_eq!(history.baseline(&current, None).unwrap().commit, "aaa111");
+        let current = record("ccc333", &[9]);
+        assert_eq!(history.baseline(&current, None).unwrap().commit, "bbb222");
+        assert_eq!(
+            history.baseline(&current, Some("aaa")).unwrap().commit,
+            "aaa111"
+        );
+        let other_input = Record {
+            input: 1,
+            ..current
+        };
+        assert_eq!(history.baseline(&other_input, None), None);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bench() {
+        let path = std::env::temp_dir().join(format!("aoc-bench-run-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let day07 = concat!(env!("CARGO_MANIFEST_DIR"), "/../day07/test_input.txt");
+        let bench = |flags: &str| {
+            let args = format!(
+                "--day 7 --input {day07} --runs 3 --history {} {flags}",
+                path.display()
+            );
+            let args = Args::parse(args.split_whitespace().map(String::from), &SPEC).unwrap();
+            let mut out = Vec::new();
+            let result = run(&args, &mut out);
+            (result, String::from_utf8(out).unwrap())
+        };
+
+        let (result, table) = bench("--commit aaa --record");
+        assert_eq!(result, Ok(()));
+        assert!(table.starts_with("| Day | Part | Median | Min | Runs |\n"));
+        assert!(table.contains("\n| 7 | 2 | "));
+        assert_eq!(History::open(&path).unwrap().records.len(), 2);
+
+        let (_, table) = bench("--commit bbb --compare");
+        assert!(table.contains(" | aaa | "), "{table}");
+        let (_, table) = bench("--commit aaa --compare");
+        assert!(table.contains(" | no baseline |"), "{table}");
+
+        // A baseline a thousand times faster
+        let history = fs::read_to_string(&path).unwrap();
+        let fast = history
+            .lines()
+            .map(|line| {
+                let (key, _) = line.rsplit_once('\t').unwrap();
+                format!("{key}\t1,1,1,1,1,1,1,1,1,1\n").replace("aaa\t", "fast\t")
+            })
+            .collect::<String>();
+        fs::write(&path, history + &fast).unwrap();
+        let (result, table) = bench("--commit bbb --compare --runs 10");
+        assert_eq!(result, Err("2 part(s) significantly slower".to_string()));
+        assert!(table.contains(" | **slower** |"), "{table}");
+        fs::remove_file(&path).unwrap();
+    }
+}
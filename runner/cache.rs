@@ -0,0 +1,201 @@
+// Answer cache for `aoc run --cached`
+//
+// One tab-separated line per answer, appended as answers are computed:
+// year, day, part, solver version, input digest, params digest, answer. An
+// entry is only used when all of its key still matches, so bumping a day's
+// `SOLVER_VERSION`, editing its input or passing other params recomputes
+// it; `--invalidate` drops a day's entries outright.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use common::params::Params;
+use common::solution::Solution;
+
+/// What an answer depends on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub version: String,
+    pub input: u64,
+    pub params: u64,
+}
+
+impl Key {
+    pub fn new(year: u32, solution: &Solution, part: u32, input: &[u8], params: &Params) -> Self {
+        let params: String = params
+            .iter()
+            .map(|(key, value)| format!("{key}={value}\n"))
+            .collect();
+        Self {
+            year,
+            day: solution.day,
+            part,
+            version: solution.version.to_string(),
+            input: crate::digest(input),
+            params: crate::digest(params.as_bytes()),
+        }
+    }
+
+    fn parse(line: &str) -> Option<(Self, String)> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [year, day, part, version, input, params, answer] = fields[..] else {
+            return None;
+        };
+        let key = Self {
+            year: year.parse().ok()?,
+            day: day.parse().ok()?,
+            part: part.parse().ok()?,
+            version: version.to_string(),
+            input: u64::from_str_radix(input, 16).ok()?,
+            params: u64::from_str_radix(params, 16).ok()?,
+        };
+        Some((key, answer.to_string()))
+    }
+
+    fn line(&self, answer: &str) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{:016x}\t{:016x}\t{answer}\n",
+            self.year, self.day, self.part, self.version, self.input, self.params
+        )
+    }
+}
+
+pub struct Cache {
+    path: PathBuf,
+    answers: HashMap<Key, String>,
+}
+
+impl Cache {
+    /// The cache at `path`, empty if there is no file yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("{}: {err}", path.display())),
+        };
+
+        let mut answers = HashMap::new();
+        for (line, number) in text.lines().zip(1..) {
+            let (key, answer) = Key::parse(line)
+                .ok_or_else(|| format!("{}:{number}: malformed cache entry", path.display()))?;
+            answers.insert(key, answer);
+        }
+        Ok(Self { path, answers })
+    }
+
+    pub fn get(&self, key: &Key) -> Option<&str> {
+        self.answers.get(key).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, key: Key, answer: String) -> Result<(), String> {
+        let line = key.line(&answer);
+        self.append(&line)
+            .map_err(|err| format!("{}: {err}", self.path.display()))?;
+        self.answers.insert(key, answer);
+        Ok(())
+    }
+
+    fn append(&self, line: &str) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())
+    }
+
+    /// Drops every entry for `part` of `day`, whatever its input, params or
+    /// version, and returns how many there were
+    pub fn invalidate(&mut self, year: u32, day: u32, part: u32) -> Result<usize, String> {
+        let before = self.answers.len();
+        self.answers
+            .retain(|key, _| (key.year, key.day, key.part) != (year, day, part));
+        let dropped = before - self.answers.len();
+        if dropped > 0 {
+            self.rewrite()
+                .map_err(|err| format!("{}: {err}", self.path.display()))?;
+        }
+        Ok(dropped)
+    }
+
+    fn rewrite(&self) -> io::Result<()> {
+        let text: String = self
+            .answers
+            .iter()
+            .map(|(key, answer)| key.line(answer))
+            .collect();
+        fs::write(&self.path, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(day: u32, version: &str, input: u64) -> Key {
+        Key {
+            year: 2025,
+            day,
+            part: 1,
+            version: version.to_string(),
+            input,
+            params: 0,
+        }
+    }
+
+    #[test]
+    fn test_cache() {
+        let path = std::env::temp_dir().join(format!("aoc-cache-{}.tsv", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut cache = Cache::open(&path).unwrap();
+        assert_eq!(cache.get(&key(7, "1", 1)), None);
+        cache.insert(key(7, "1", 1), "13".to_string()).unwrap();
+        cache.insert(key(8, "1", 1), "20".to_string()).unwrap();
+
+        // Persisted, and only an exact key matches
+        let mut cache = Cache::open(&path).unwrap();
+        assert_eq!(cache.get(&key(7, "1", 1)), Some("13"));
+        assert_eq!(cache.get(&key(7, "2", 1)), None);
+        assert_eq!(cache.get(&key(7, "1", 2)), None);
+
+        assert_eq!(cache.invalidate(2025, 7, 1), Ok(1));
+        assert_eq!(cache.invalidate(2025, 7, 1), Ok(0));
+        let cache = Cache::open(&path).unwrap();
+        assert_eq!(cache.get(&key(7, "1", 1)), None);
+        assert_eq!(cache.get(&key(8, "1", 1)), Some("20"));
+
+        fs::write(&path, "2025\t7\n").unwrap();
+        let err = Cache::open(&path).err().unwrap();
+        assert!(err.ends_with(":1: malformed cache entry"), "{err}");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_key() {
+        let day11 = year2025::YEAR.day(11).unwrap();
+        let params = |via: &str| {
+            let args = [format!("--param=via={via}")];
+            Params::from_sources("day11", day11.defaults, None, args).unwrap()
+        };
+        let key = |input: &[u8], via: &str| Key::new(2025, day11, 2, input, &params(via));
+        assert_eq!(key(b"a: b\n", "dac,fft"), key(b"a: b\n", "dac,fft"));
+        assert_ne!(key(b"a: b\n", "dac,fft"), key(b"a: b\n", "fft"));
+        assert_ne!(key(b"a: b\n", "dac,fft"), key(b"a: c\n", "dac,fft"));
+        assert_eq!(key(b"", "fft").version, day11.version);
+
+        let line = key(b"a: b\n", "fft").line("42");
+        assert_eq!(
+            Key::parse(line.trim_end()),
+            Some((key(b"a: b\n", "fft"), "42".to_string()))
+        );
+    }
+}
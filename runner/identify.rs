@@ -0,0 +1,208 @@
+// `aoc identify FILE`: which day an anonymous input belongs to
+//
+// Every day's parser gets a try, and has to accept the whole file: a parser
+// that panics on it rejects it. Among the days that accept the file, each
+// is scored by how closely the file's shape matches the day's example
+// (test_input.txt, which also seeds its fuzz test). The shape is a
+// histogram of byte classes: digits are lumped together, as are lowercase
+// letters, and every other byte counts as itself, so `L68`, `11-22,` and
+// `[.##.] (3) {3,5}` all look different.
+
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+
+use common::scan;
+use common::solution::{Solution, Year};
+
+use crate::args::{Args, Spec};
+
+pub const SPEC: Spec = Spec {
+    values: &["year"],
+    flags: &[],
+};
+
+/// Best scores closer than this are reported as a tie
+const MARGIN: f64 = 0.1;
+
+/// Below this, a day's parser accepted the file but it looks nothing like
+/// the day's inputs
+const MIN_SCORE: f64 = 0.4;
+
+/// A day whose parser accepts the file
+pub struct Candidate {
+    pub year: u32,
+    pub solution: &'static Solution,
+    /// Similarity to the day's example, from 0 to 1
+    pub score: f64,
+}
+
+impl Candidate {
+    pub fn name(&self) -> String {
+        format!("{} {}", self.year, self.solution.name())
+    }
+}
+
+fn class(byte: u8) -> u8 {
+    match byte {
+        b'0'..=b'9' => b'0',
+        b'a'..=b'z' => b'a',
+        _ => byte,
+    }
+}
+
+fn shape(input: &[u8]) -> [f64; 256] {
+    let mut counts = [0.0; 256];
+    for &byte in input.iter().filter(|&&byte| byte != b'\r') {
+        counts[class(byte) as usize] += 1.0;
+    }
+    counts
+}
+
+/// Cosine similarity of two shapes
+fn similarity(a: &[f64; 256], b: &[f64; 256]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f64; 256]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    match norm(a) * norm(b) {
+        0.0 => 0.0,
+        norms => dot / norms,
+    }
+}
+
+/// The days of `years` that accept `input`, best match first
+pub fn candidates(years: &[&'static Year], input: &[u8]) -> Result<Vec<Candidate>, String> {
+    let shape = shape(input);
+    let mut candidates = Vec::new();
+    for year in years {
+        for solution in year.days {
+            let accepts = panic::catch_unwind(AssertUnwindSafe(|| (solution.validate)(input)));
+            if !matches!(accepts, Ok(Ok(()))) {
+                continue;
+            }
+            let path = solution.seed_path();
+            let seed =
+                scan::read_input(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+            candidates.push(Candidate {
+                year: year.year,
+                solution,
+                score: similarity(&shape, &self::shape(&seed)),
+            });
+        }
+    }
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(candidates)
+}
+
+/// The likely day(s): the best candidate and any within `MARGIN` of it,
+/// unless even the best is under `MIN_SCORE`
+pub fn likely(candidates: &[Candidate]) -> &[Candidate] {
+    let Some(best) = candidates.first().filter(|best| best.score >= MIN_SCORE) else {
+        return &[];
+    };
+    let tied = candidates
+        .iter()
+        .take_while(|candidate| best.score - candidate.score < MARGIN)
+        .count();
+    &candidates[..tied]
+}
+
+pub fn run(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let [path] = args.positional.as_slice() else {
+        return Err("identify needs exactly one FILE".to_string());
+    };
+    let years = match args.number("year")? {
+        Some(year) => vec![crate::year(Some(year))?],
+        None => crate::YEARS.to_vec(),
+    };
+    let input = scan::read_input(path).map_err(|err| format!("{path}: {err}"))?;
+
+    let candidates = candidates(&years, &input)?;
+    let likely: Vec<String> = likely(&candidates).iter().map(Candidate::name).collect();
+    if likely.is_empty() {
+        return Err(format!("{path}: doesn't look like any day's input"));
+    }
+
+    let write = |out: &mut dyn Write| -> std::io::Result<()> {
+        for candidate in &candidates {
+            let percent = (candidate.score * 100.0).round();
+            writeln!(out, "{}: parses, {percent}% match", candidate.name())?;
+        }
+        writeln!(out, "likely {}", likely.join(" or "))
+    };
+    write(out).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn likely_days(input: &[u8]) -> Vec<u32> {
+        let candidates = candidates(crate::YEARS, input).unwrap();
+        likely(&candidates)
+            .iter()
+            .map(|candidate| candidate.solution.day)
+            .collect()
+    }
+
+    #[test]
+    fn test_examples_identify_themselves() {
+        for solution in year2025::YEAR.days {
+            let seed = scan::read_input(solution.seed_path()).unwrap();
+            assert_eq!(likely_days(&seed), [solution.day], "{}", solution.name());
+        }
+    }
+
+    #[test]
+    fn test_identify() {
+        // Fragments that are not any example
+        assert_eq!(likely_days(b"L5\nR1000\nL3\n"), [1]);
+        assert_eq!(likely_days(b"5-9,100-200\n"), [2]);
+        assert_eq!(likely_days(b"a: b c\nb: out\nc: out\n"), [11]);
+        assert!(likely_days(b"not a puzzle input").is_empty());
+    }
+
+    #[test]
+    fn test_panicking_parser() {
+        const DAYS: &[Solution] = &[Solution {
+            day: 1,
+            parts: 1,
+            dir: ".",
+            version: "1",
+            defaults: &[],
+            units: &[],
+            validate: |_| panic!("validate panicked"),
+            approach: "",
+            svg: None,
+            generate: None,
+            solve: |_, _, _| Ok(String::new()),
+        }];
+        const YEAR: Year = Year {
+            year: 2000,
+            days: DAYS,
+        };
+        assert!(candidates(&[&YEAR], b"L5\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_run() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../day07/input.txt");
+        let mut out = Vec::new();
+        crate::main(["identify".to_string(), path.to_string()], &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("2025 day07: parses, 100% match\n"));
+        assert!(out.ends_with("likely 2025 day07\n"));
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let args = ["identify".to_string(), path.to_string()];
+        assert!(crate::main(args, &mut Vec::new())
+            .unwrap_err()
+            .ends_with("doesn't look like any day's input"));
+    }
+
+    #[test]
+    fn test_shape() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        assert!(close(similarity(&shape(b"L6"), &shape(b"R1")), 0.5));
+        assert!(close(similarity(&shape(b"L6\n"), &shape(b"L1\r\n")), 1.0));
+        assert_eq!(similarity(&shape(b""), &shape(b"L1")), 0.0);
+    }
+}
@@ -0,0 +1,192 @@
+// Advent of Code runner - the `aoc` command
+//
+// Every (year, day) is found through the year crates' `Solution` tables, so
+// the runner never names a day itself: `aoc run --year 2025 --day 7` looks
+// day 7 up in `year2025::YEAR`.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use common::solution::Year;
+
+pub mod args;
+pub mod bench;
+pub mod cache;
+pub mod identify;
+pub mod memory;
+pub mod report;
+pub mod run;
+pub mod scale;
+pub mod serve;
+
+use args::{Args, Spec};
+
+#[global_allocator]
+static ALLOCATOR: memory::Counting = memory::Counting;
+
+/// Every year the runner knows, oldest first
+pub const YEARS: &[&Year] = &[&year2025::YEAR];
+
+pub const USAGE: &str = "\
+usage: aoc <command> [options]
+
+commands:
+  run [--year Y] (--day D | --all) [--part P] [--input FILE] [--param key=value]...
+      [--cached] [--refresh] [--invalidate] [--cache FILE]
+      print answers and timings; --input and --param need a single --day.
+      --cached reuses answers for unchanged inputs, params and solvers,
+      --refresh recomputes and re-stores them, --invalidate drops the
+      selected days' entries first; the cache is .aoc/answers.tsv
+  bench [--year Y] (--day D | --all) [--part P] [--input FILE] [--runs N]
+      [--record] [--compare] [--baseline COMMIT] [--commit LABEL] [--history FILE]
+      time each part N times (10) and print a markdown summary; --record
+      appends the times to .aoc/bench.tsv under the current commit, and
+      --compare flags significant changes against the latest other commit
+  identify [--year Y] FILE
+      guess which day FILE is an input for
+  report [--year Y] [--output FILE]
+      run every day and write REPORT.md: answers, timings, peak memory,
+      approaches and source links
+  scale [--year Y] --day D [--part P] [--param key=value]... [--from N] [--factor F]
+      [--steps S] [--runs R] [--budget SECS] [--svg FILE]
+      time the fastest of R runs (3) on the day's generated inputs at sizes
+      N, N*F, ... (16, 2, up to 8 sizes, stopping once a run takes over SECS,
+      1) and fit the exponent of the growth on a log-log plot; --svg also
+      writes the plot as SVG
+  serve [--port P] [--history FILE]
+      serve a dashboard on http://127.0.0.1:P/ (8025): run any part, see
+      the spatial days' drawings and the bench history
+  list
+      print every known year and day
+
+run, bench and serve also take --data-dir DIR for their state files, else
+$AOC_DATA_DIR, else .aoc in the working directory";
+
+/// Names the data directory when there is no `--data-dir`
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+
+/// Where `aoc` is run from, which holds its .aoc and REPORT.md. The tests
+/// run in the runner's own directory, so they use the workspace root
+pub fn workspace() -> PathBuf {
+    if cfg!(test) {
+        PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+    } else {
+        PathBuf::from(".")
+    }
+}
+
+/// Where the runner keeps its state: `--data-dir`, else $AOC_DATA_DIR,
+/// else .aoc in the working directory
+pub fn data_dir(args: &Args) -> PathBuf {
+    args.value("data-dir")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os(DATA_DIR_VAR).map(PathBuf::from))
+        .unwrap_or_else(|| workspace().join(".aoc"))
+}
+
+/// 64-bit FNV-1a, to tell inputs apart without storing them
+pub fn digest(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3)
+    })
+}
+
+/// `year`, or the latest one when it is not given
+pub fn year(year: Option<u32>) -> Result<&'static Year, String> {
+    match year {
+        Some(year) => YEARS
+            .iter()
+            .copied()
+            .find(|known| known.year == year)
+            .ok_or_else(|| format!("no solutions for {year}")),
+        None => YEARS.last().copied().ok_or_else(|| "no years".to_string()),
+    }
+}
+
+/// Runs the command in `args` (without the program name), writing its
+/// output to `out`
+pub fn main(args: impl IntoIterator<Item = String>, out: &mut impl Write) -> Result<(), String> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("run") => run::run(&Args::parse(args, &run::SPEC)?, out),
+        Some("bench") => bench::run(&Args::parse(args, &bench::SPEC)?, out),
+        Some("identify") => identify::run(&Args::parse(args, &identify::SPEC)?, out),
+        Some("report") => report::run(&Args::parse(args, &report::SPEC)?, out),
+        Some("scale") => scale::run(&Args::parse(args, &scale::SPEC)?, out),
+        Some("serve") => serve::serve(&Args::parse(args, &serve::SPEC)?, out),
+        Some("list") => {
+            Args::parse(
+                args,
+                &Spec {
+                    values: &[],
+                    flags: &[],
+                },
+            )?;
+            list(out).map_err(|err| err.to_string())
+        }
+        Some("help" | "--help") => writeln!(out, "{USAGE}").map_err(|err| err.to_string()),
+        Some(command) => Err(format!("unknown command {command}\n{USAGE}")),
+        None => Err(USAGE.to_string()),
+    }
+}
+
+fn list(out: &mut impl Write) -> std::io::Result<()> {
+    for year in YEARS {
+        for solution in year.days {
+            let parts = match solution.parts {
+                1 => "part 1".to_string(),
+                parts => format!("parts 1-{parts}"),
+            };
+            writeln!(out, "{} {} {parts}", year.year, solution.name())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(args: &str) -> Result<String, String> {
+        let mut out = Vec::new();
+        main(args.split_whitespace().map(String::from), &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_list() {
+        let listing = output("list").unwrap();
+        assert_eq!(listing.lines().count(), 12);
+        assert!(listing.starts_with("2025 day01 parts 1-2\n"));
+        assert!(listing.ends_with("2025 day12 part 1\n"));
+    }
+
+    #[test]
+    fn test_digest() {
+        assert_eq!(digest(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(digest(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(digest(b"ab"), digest(b"ba"));
+    }
+
+    #[test]
+    fn test_data_dir() {
+        let parse = |args: &str| Args::parse(args.split_whitespace().map(String::from), &run::SPEC);
+        let args = parse("--data-dir /tmp/state").unwrap();
+        assert_eq!(data_dir(&args), PathBuf::from("/tmp/state"));
+        if std::env::var_os(DATA_DIR_VAR).is_none() {
+            assert_eq!(data_dir(&parse("").unwrap()), workspace().join(".aoc"));
+        }
+        assert!(workspace().join("Cargo.toml").exists());
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(year(None).unwrap().year, 2025);
+        assert_eq!(year(Some(2015)).err().unwrap(), "no solutions for 2015");
+        assert!(output("help").unwrap().starts_with("usage: aoc"));
+        assert!(output("fly")
+            .unwrap_err()
+            .starts_with("unknown command fly\n"));
+        assert_eq!(output("list --all").unwrap_err(), "unknown option --all");
+    }
+}
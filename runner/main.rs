@@ -0,0 +1,12 @@
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let stdout = std::io::stdout();
+    match runner::main(std::env::args().skip(1), &mut stdout.lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("aoc: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
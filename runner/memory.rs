@@ -0,0 +1,116 @@
+// Peak heap use, for `aoc report`
+//
+// A counting wrapper around the system allocator, installed as the runner's
+// global allocator. It only counts while `peak` is measuring: at any other
+// time, such as `aoc bench`'s timed runs, an allocation is the system
+// allocator's plus one relaxed load. Only the heap is seen, and allocations
+// on other threads count too, so a measurement is only meaningful while
+// nothing else runs.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+/// `peak` calls in progress; counting is on while this is non-zero
+static MEASURING: AtomicUsize = AtomicUsize::new(0);
+/// Bytes allocated minus bytes freed while counting. Blocks allocated before
+/// counting started can be freed during it, so this can go below zero
+static CURRENT: AtomicIsize = AtomicIsize::new(0);
+static PEAK: AtomicIsize = AtomicIsize::new(0);
+
+pub struct Counting;
+
+impl Counting {
+    fn counting() -> bool {
+        MEASURING.load(Ordering::Relaxed) > 0
+    }
+
+    fn grew(size: usize) {
+        if Self::counting() {
+            let now = CURRENT.fetch_add(size as isize, Ordering::Relaxed) + size as isize;
+            PEAK.fetch_max(now, Ordering::Relaxed);
+        }
+    }
+
+    fn shrank(size: usize) {
+        if Self::counting() {
+            CURRENT.fetch_sub(size as isize, Ordering::Relaxed);
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::shrank(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            Self::shrank(layout.size());
+            Self::grew(new_size);
+        }
+        new
+    }
+}
+
+/// Heap bytes allocated at the highest point while running `f`, on top of
+/// what was allocated before it; the allocator only counts in here
+pub fn peak<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    MEASURING.fetch_add(1, Ordering::Relaxed);
+    let result = f();
+    MEASURING.fetch_sub(1, Ordering::Relaxed);
+    let peak = (PEAK.load(Ordering::Relaxed) - base).max(0) as usize;
+    (result, peak)
+}
+
+/// `bytes` in B, KiB or MiB
+pub fn format(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peak() {
+        // Other tests allocate concurrently, so only a lower bound holds
+        let (len, peak) = peak(|| {
+            let big = vec![1u8; 1 << 20];
+            drop(std::hint::black_box(big));
+            vec![0u8; 10].len()
+        });
+        assert_eq!(len, 10);
+        assert!(peak >= 1 << 20, "{peak}");
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(format(512), "512 B");
+        assert_eq!(format(1536), "1.5 KiB");
+        assert_eq!(format(3 << 20), "3.0 MiB");
+    }
+}
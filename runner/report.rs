@@ -0,0 +1,180 @@
+// `aoc report`: REPORT.md, generated from a real run of every day
+//
+// Each day's parser is timed alone, then each part, which parses again, and
+// the table gets the answers, the timings, the peak heap use across all of
+// them, the day's approach and a link to its source. The peak comes from a
+// second pass, so counting allocations doesn't slow the timed one. Days use
+// their default params, so the answers are the puzzle's.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use common::scan;
+use common::solution::{Solution, SolveError, Year};
+
+use crate::args::{Args, Spec};
+use crate::memory;
+
+pub const SPEC: Spec = Spec {
+    values: &["year", "output"],
+    flags: &[],
+};
+
+/// One day's line of the report
+pub struct Row {
+    pub solution: &'static Solution,
+    pub parse: Duration,
+    /// Each part's answer and time, part 1 first
+    pub parts: Vec<(Result<String, SolveError>, Duration)>,
+    /// Heap bytes at the highest point of any of the runs
+    pub peak: usize,
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+pub fn measure(solution: &'static Solution, input: &[u8]) -> Result<Row, String> {
+    let params = solution
+        .default_params()
+        .map_err(|err| format!("{}: {err}", solution.name()))?;
+
+    let (_, parse) = timed(|| (solution.validate)(input));
+    let parts: Vec<_> = (1..=solution.parts)
+        .map(|part| timed(|| solution.run(part, input, &params)))
+        .collect();
+
+    let (_, mut peak) = memory::peak(|| (solution.validate)(input));
+    for part in 1..=solution.parts {
+        let (_, part_peak) = memory::peak(|| solution.run(part, input, &params));
+        peak = peak.max(part_peak);
+    }
+    Ok(Row {
+        solution,
+        parse,
+        parts,
+        peak,
+    })
+}
+
+/// The day's library source, relative to the workspace root
+fn source(solution: &Solution) -> String {
+    let dir = Path::new(solution.dir)
+        .file_name()
+        .map_or_else(|| solution.name(), |dir| dir.to_string_lossy().into_owned());
+    format!("{dir}/{}.rs", solution.name())
+}
+
+pub fn render(year: &Year, rows: &[Row], out: &mut impl Write) -> io::Result<()> {
+    let build = match cfg!(debug_assertions) {
+        true => "debug",
+        false => "release",
+    };
+    writeln!(out, "# Advent of Code {}", year.year)?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Generated by `cargo run --release -p runner -- report` from one run of each \
+         day on its input.txt, in a {build} build. Parse is the parser alone; each \
+         part's time includes parsing again. Peak is the most heap in use above the \
+         input itself during any of them."
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "| Day | Part 1 | Part 2 | Parse | Part 1 time | Part 2 time | Peak | Approach | Source |"
+    )?;
+    writeln!(out, "|---|---|---|---|---|---|---|---|---|")?;
+
+    for row in rows {
+        let answer = |part: usize| match row.parts.get(part) {
+            Some((Ok(answer), _)) => answer.clone(),
+            Some((Err(err), _)) => format!("error: {err}"),
+            None => "-".to_string(),
+        };
+        let time = |part: usize| {
+            row.parts
+                .get(part)
+                .map_or_else(|| "-".to_string(), |(_, time)| format!("{time:.2?}"))
+        };
+        let source = source(row.solution);
+        writeln!(
+            out,
+            "| {} | {} | {} | {:.2?} | {} | {} | {} | {} | [{source}]({source}) |",
+            row.solution.day,
+            answer(0),
+            answer(1),
+            row.parse,
+            time(0),
+            time(1),
+            memory::format(row.peak),
+            row.solution.approach,
+        )?;
+    }
+    Ok(())
+}
+
+/// `--output`, or REPORT.md in the working directory
+pub fn output_path(args: &Args) -> PathBuf {
+    args.value("output")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| crate::workspace().join("REPORT.md"))
+}
+
+pub fn run(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let year = crate::year(args.number("year")?)?;
+    let mut rows = Vec::new();
+    for solution in year.days {
+        let path = solution.input_path();
+        let input = scan::read_input(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+        rows.push(measure(solution, &input)?);
+    }
+
+    let mut report = Vec::new();
+    render(year, &rows, &mut report).map_err(|err| err.to_string())?;
+    let path = output_path(args);
+    fs::write(&path, report).map_err(|err| format!("{}: {err}", path.display()))?;
+    writeln!(out, "wrote {}", path.display()).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let year = &year2025::YEAR;
+        let rows: Vec<Row> = [7, 12]
+            .into_iter()
+            .map(|day| {
+                let solution = year.day(day).unwrap();
+                let input = scan::read_input(solution.seed_path()).unwrap();
+                measure(solution, &input).unwrap()
+            })
+            .collect();
+        assert_eq!(rows[0].parts.len(), 2);
+        assert!(rows[0].peak > 0);
+
+        let mut out = Vec::new();
+        render(year, &rows, &mut out).unwrap();
+        let report = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "# Advent of Code 2025");
+        assert_eq!(lines.len(), 8);
+
+        // Timings and memory vary from run to run
+        let cells = |line: &str| -> Vec<String> {
+            line.split(" | ").map(str::to_string).collect::<Vec<_>>()
+        };
+        let day07 = cells(lines[6]);
+        assert_eq!(day07[..3], ["| 7", "13", "18"]);
+        assert_eq!(day07[8], "[day07/day07.rs](day07/day07.rs) |");
+        let day12 = cells(lines[7]);
+        assert_eq!(day12[..3], ["| 12", "3", "-"]);
+        assert_eq!(day12[5], "-");
+    }
+}
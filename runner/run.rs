@@ -0,0 +1,277 @@
+// `aoc run`: answers and timings for one day or a whole year
+//
+// Without `--input`, each day reads the input.txt in its own crate
+// directory, so the runner works from anywhere in the tree. With
+// `--cached`, answers come from the cache when nothing they depend on has
+// changed, and are shown as `(cached)` instead of a timing.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use common::params::Params;
+use common::scan;
+use common::solution::{Solution, SolveError, Year};
+
+use crate::args::{Args, Spec};
+use crate::cache::{Cache, Key};
+
+pub const SPEC: Spec = Spec {
+    values: &["year", "day", "part", "input", "param", "cache", "data-dir"],
+    flags: &["all", "cached", "refresh", "invalidate"],
+};
+
+/// What `aoc run` was asked for
+pub struct Selection {
+    pub year: &'static Year,
+    pub days: Vec<&'static Solution>,
+    pub part: Option<u32>,
+}
+
+impl Selection {
+    pub fn from_args(args: &Args) -> Result<Self, String> {
+        let year = crate::year(args.number("year")?)?;
+        let days = match (args.number::<u32>("day")?, args.flag("all")) {
+            (Some(day), false) => vec![year
+                .day(day)
+                .ok_or_else(|| format!("no day {day} in {}", year.year))?],
+            (None, true) => year.days.iter().collect(),
+            _ => return Err("pass either --day D or --all".to_string()),
+        };
+
+        let part = args.number("part")?;
+        if let (Some(part), [solution]) = (part, days.as_slice()) {
+            if !(1..=solution.parts).contains(&part) {
+                return Err(format!("{} has no part {part}", solution.name()));
+            }
+        }
+        Ok(Self { year, days, part })
+    }
+
+    /// The parts to run of `solution`; with `--all --part 2`, a day
+    /// without a part 2 is skipped
+    pub fn parts(&self, solution: &Solution) -> Vec<u32> {
+        (1..=solution.parts)
+            .filter(|&part| self.part.is_none_or(|wanted| wanted == part))
+            .collect()
+    }
+}
+
+/// One part's answer and how long computing it took
+pub struct Outcome {
+    pub part: u32,
+    pub answer: Result<String, SolveError>,
+    pub elapsed: Duration,
+}
+
+pub fn solve(solution: &Solution, part: u32, input: &[u8], params: &Params) -> Outcome {
+    let start = Instant::now();
+    let answer = solution.run(part, input, params);
+    Outcome {
+        part,
+        answer,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// `--input`, or the day's own input.txt
+pub fn input_path(args: &Args, solution: &Solution) -> PathBuf {
+    args.value("input")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| solution.input_path())
+}
+
+/// The day's params: defaults, then aoc.toml, then `--param`
+pub fn params(args: &Args, solution: &Solution) -> Result<Params, String> {
+    let args = args.values("param").map(|entry| format!("--param={entry}"));
+    Params::load_with(&solution.name(), solution.defaults, args)
+        .map_err(|err| format!("{}: {err}", solution.name()))
+}
+
+/// `--cache`, or .aoc/answers.tsv
+pub fn cache_path(args: &Args) -> PathBuf {
+    args.value("cache")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| crate::data_dir(args).join("answers.tsv"))
+}
+
+pub fn run(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let selection = Selection::from_args(args)?;
+    if selection.days.len() > 1 && (args.value("input").is_some() || args.value("param").is_some())
+    {
+        return Err("--input and --param need a single --day".to_string());
+    }
+
+    let year = selection.year.year;
+    let (cached, refresh, invalidate) = (
+        args.flag("cached"),
+        args.flag("refresh"),
+        args.flag("invalidate"),
+    );
+    let mut cache = match cached || invalidate {
+        true => Some(Cache::open(cache_path(args))?),
+        false => None,
+    };
+    if let (true, Some(cache)) = (invalidate, &mut cache) {
+        for solution in &selection.days {
+            for part in selection.parts(solution) {
+                cache.invalidate(year, solution.day, part)?;
+            }
+        }
+    }
+    let mut cache = cache.filter(|_| cached);
+
+    let mut failed = 0;
+    for solution in &selection.days {
+        let path = input_path(args, solution);
+        let input = scan::read_input(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+        let params = params(args, solution)?;
+
+        for part in selection.parts(solution) {
+            let key = Key::new(year, solution, part, &input, &params);
+            let hit = cache
+                .as_ref()
+                .filter(|_| !refresh)
+                .and_then(|cache| cache.get(&key));
+            let (answer, timing) = match hit {
+                Some(answer) => (answer.to_string(), "cached".to_string()),
+                None => {
+                    let outcome = solve(solution, part, &input, &params);
+                    let answer = match (outcome.answer, &mut cache) {
+                        (Ok(answer), Some(cache)) => {
+                            cache.insert(key, answer.clone())?;
+                            answer
+                        }
+                        (Ok(answer), None) => answer,
+                        (Err(err), _) => {
+                            failed += 1;
+                            format!("error: {err}")
+                        }
+                    };
+                    (answer, format!("{:.2?}", outcome.elapsed))
+                }
+            };
+            writeln!(
+                out,
+                "{year} {} part {part}: {answer} ({timing})",
+                solution.name()
+            )
+            .map_err(|err| err.to_string())?;
+        }
+    }
+
+    match failed {
+        0 => Ok(()),
+        failed => Err(format!("{failed} part(s) failed")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Args {
+        Args::parse(args.split_whitespace().map(String::from), &SPEC).unwrap()
+    }
+
+    /// The output without the timings
+    fn answers(args: &str) -> Result<Vec<String>, String> {
+        let mut out = Vec::new();
+        run(&self::args(args), &mut out)?;
+        Ok(String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| line.rsplit_once(" (").unwrap().0.to_string())
+            .collect())
+    }
+
+    fn test_input(day: &str) -> String {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../").to_string() + day + "/test_input.txt"
+    }
+
+    #[test]
+    fn test_run_day() {
+        let day07 = test_input("day07");
+        assert_eq!(
+            answers(&format!("--year 2025 --day 7 --input {day07}")),
+            Ok(vec![
+                "2025 day07 part 1: 13".to_string(),
+                "2025 day07 part 2: 18".to_string()
+            ])
+        );
+        assert_eq!(
+            answers(&format!("--day 7 --part 2 --input {day07}")),
+            Ok(vec!["2025 day07 part 2: 18".to_string()])
+        );
+
+        // The part 2 route, overridden
+        let day11 = test_input("day11");
+        assert_eq!(
+            answers(&format!(
+                "--day 11 --part 2 --input {day11} --param via=fft"
+            )),
+            Ok(vec!["2025 day11 part 2: 2".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_selection() {
+        let selection = Selection::from_args(&args("--all --part 2")).unwrap();
+        assert_eq!(selection.days.len(), 12);
+        assert_eq!(selection.parts(selection.days[0]), [2]);
+        assert!(selection.parts(selection.days[11]).is_empty());
+
+        let error = |args: &str| Selection::from_args(&self::args(args)).err().unwrap();
+        assert_eq!(error("--day 13"), "no day 13 in 2025");
+        assert_eq!(error("--day 12 --part 2"), "day12 has no part 2");
+        assert_eq!(error("--day 1 --all"), "pass either --day D or --all");
+        assert_eq!(error("--year 2024 --day 1"), "no solutions for 2024");
+        assert_eq!(
+            answers("--all --param via=fft"),
+            Err("--input and --param need a single --day".to_string())
+        );
+    }
+
+    #[test]
+    fn test_failures_are_reported() {
+        let day07 = test_input("day07");
+        assert_eq!(
+            answers(&format!("--day 9 --input {day07}")),
+            Err("2 part(s) failed".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cached() {
+        let cache = std::env::temp_dir().join(format!("aoc-run-cache-{}.tsv", std::process::id()));
+        let _ = std::fs::remove_file(&cache);
+        let day07 = test_input("day07");
+        let output = |flags: &str| {
+            let mut out = Vec::new();
+            let args = format!(
+                "--day 7 --input {day07} --cache {} {flags}",
+                cache.display()
+            );
+            run(&self::args(&args), &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert!(!output("--cached").contains("(cached)"));
+        assert_eq!(
+            output("--cached"),
+            "2025 day07 part 1: 13 (cached)\n2025 day07 part 2: 18 (cached)\n"
+        );
+        // A stored answer is trusted until refreshed or invalidated
+        let text = std::fs::read_to_string(&cache).unwrap();
+        std::fs::write(&cache, text.replace("\t13\n", "\t99\n")).unwrap();
+        assert!(output("--cached --part 1").contains(": 99 (cached)"));
+        assert!(!output("--cached --part 1 --refresh").contains("(cached)"));
+        assert!(output("--cached --part 1").contains(": 13 (cached)"));
+        assert!(!output("--cached --invalidate").contains("(cached)"));
+        assert!(output("--cached").contains(": 18 (cached)"));
+
+        // Without --cached the cache is not touched
+        assert!(!output("").contains("(cached)"));
+        std::fs::remove_file(&cache).unwrap();
+    }
+}
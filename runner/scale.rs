@@ -0,0 +1,342 @@
+// Empirical complexity: how a day's solve time grows with its input
+//
+// `aoc scale --day D` runs the day's input generator at geometrically growing
+// sizes, times each part at every size (the fastest of a few runs) and fits a
+// line through log(time) against log(size). Its slope is the empirical
+// exponent: about 1 for a linear solver, 2 for a quadratic one. Sizes stop
+// growing once a run takes longer than the budget (after the first two), so
+// a slow solver still finishes.
+
+use std::fs;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use common::params::Params;
+use common::solution::Solution;
+use common::svg::Svg;
+
+use crate::args::{Args, Spec};
+use crate::bench::nanos;
+use crate::run::{params, Selection};
+
+pub const SPEC: Spec = Spec {
+    values: &[
+        "year", "day", "part", "param", "from", "factor", "steps", "runs", "budget", "svg",
+    ],
+    flags: &[],
+};
+
+const DEFAULT_FROM: usize = 16;
+const DEFAULT_FACTOR: usize = 2;
+const DEFAULT_STEPS: usize = 8;
+const DEFAULT_RUNS: usize = 3;
+/// Seconds a run may take before the sizes stop growing
+const DEFAULT_BUDGET: f64 = 1.0;
+
+/// Every size is generated from the same seed, so reruns time the same inputs
+const SEED: u64 = 0x2025_1201;
+
+const PLOT_WIDTH: usize = 60;
+const PLOT_HEIGHT: usize = 16;
+const COLORS: &[&str] = &["#2e86c1", "#c0392b", "#27ae60"];
+
+/// The fastest run at one size
+#[derive(Debug, Clone, Copy)]
+pub struct Sample {
+    pub size: usize,
+    pub seconds: f64,
+}
+
+impl Sample {
+    /// (ln size, ln seconds), clamping the time so a zero reading stays finite
+    fn log(&self) -> (f64, f64) {
+        ((self.size as f64).ln(), self.seconds.max(1e-9).ln())
+    }
+}
+
+/// One part's timings and the line through them
+pub struct Curve {
+    pub part: u32,
+    pub samples: Vec<Sample>,
+    /// Slope (the exponent) and intercept of ln(seconds) against ln(size)
+    pub fit: (f64, f64),
+}
+
+impl Curve {
+    fn at(&self, log_size: f64) -> f64 {
+        self.fit.0 * log_size + self.fit.1
+    }
+}
+
+/// Least-squares line through the samples on log-log axes; `None` without
+/// two distinct sizes
+pub fn fit(samples: &[Sample]) -> Option<(f64, f64)> {
+    let points: Vec<(f64, f64)> = samples.iter().map(Sample::log).collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    (points.len() >= 2 && sxx > 0.0).then(|| {
+        let slope = sxy / sxx;
+        (slope, mean_y - slope * mean_x)
+    })
+}
+
+/// Fastest of `runs` runs of `part`, in seconds
+fn time(
+    solution: &Solution,
+    part: u32,
+    input: &[u8],
+    params: &Params,
+    runs: usize,
+) -> Result<f64, String> {
+    let mut best = Duration::MAX;
+    for _ in 0..runs {
+        let start = Instant::now();
+        solution
+            .run(part, input, params)
+            .map_err(|err| format!("{} part {part}: {err}", solution.name()))?;
+        best = best.min(start.elapsed());
+    }
+    Ok(best.as_secs_f64())
+}
+
+/// Smallest and largest of `values`
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)))
+}
+
+/// The curve as a log-log scatter in text: samples as `*` over the fitted
+/// line as `.`, with the time and size ranges on the axes
+pub fn plot(curve: &Curve) -> String {
+    let (x0, x1) = bounds(curve.samples.iter().map(|s| s.log().0));
+    let fitted = [curve.at(x0), curve.at(x1)];
+    let (y0, y1) = bounds(curve.samples.iter().map(|s| s.log().1).chain(fitted));
+    let scale = |v: f64, lo: f64, hi: f64, cells: usize| {
+        ((v - lo) / (hi - lo).max(f64::EPSILON) * (cells - 1) as f64).round() as usize
+    };
+
+    let mut grid = vec![vec![' '; PLOT_WIDTH]; PLOT_HEIGHT];
+    let line = (0..PLOT_WIDTH).map(|col| x0 + (x1 - x0) * col as f64 / (PLOT_WIDTH - 1) as f64);
+    for (col, x) in line.enumerate() {
+        let row = scale(curve.at(x), y0, y1, PLOT_HEIGHT);
+        grid[PLOT_HEIGHT - 1 - row][col] = '.';
+    }
+    for sample in &curve.samples {
+        let (x, y) = sample.log();
+        let (row, col) = (scale(y, y0, y1, PLOT_HEIGHT), scale(x, x0, x1, PLOT_WIDTH));
+        grid[PLOT_HEIGHT - 1 - row][col] = '*';
+    }
+
+    let (top, bottom) = (nanos(y1.exp() * 1e9), nanos(y0.exp() * 1e9));
+    // Width in characters: µs takes two bytes
+    let margin = top.chars().count().max(bottom.chars().count());
+    let mut out = String::new();
+    for (row, cells) in grid.iter().enumerate() {
+        let label = match row {
+            0 => top.as_str(),
+            row if row == PLOT_HEIGHT - 1 => bottom.as_str(),
+            _ => "",
+        };
+        let cells: String = cells.iter().collect();
+        out.push_str(&format!("{label:>margin$} |{}\n", cells.trim_end()));
+    }
+    out.push_str(&format!("{:margin$} +{}\n", "", "-".repeat(PLOT_WIDTH)));
+    let (first, last) = (x0.exp().round(), x1.exp().round());
+    let last = format!("{last}");
+    out.push_str(&format!(
+        "{:margin$}  {first:<width$}{last}\n",
+        "",
+        width = PLOT_WIDTH.saturating_sub(last.len())
+    ));
+    out
+}
+
+/// Every curve on one pair of log-log axes, each part in its own colour,
+/// for `--svg`
+pub fn svg(name: &str, curves: &[Curve]) -> String {
+    // SVG's y axis points down, so times are drawn negated
+    let points = || {
+        curves
+            .iter()
+            .flat_map(|c| c.samples.iter().map(Sample::log))
+    };
+    let (x0, x1) = bounds(points().map(|(x, _)| x));
+    let (y0, y1) = bounds(points().map(|(_, y)| -y));
+    let extent = (x1 - x0).max(y1 - y0).max(f64::EPSILON);
+    let (pad, dot) = (extent / 20.0, extent / 100.0);
+
+    let mut svg = Svg::with_origin(x0 - pad, y0 - pad, x1 - x0 + 2.0 * pad, y1 - y0 + 2.0 * pad);
+    let exponents: Vec<String> = curves
+        .iter()
+        .map(|c| format!("part {} ~ size^{:.2}", c.part, c.fit.0))
+        .collect();
+    svg.title(&format!("{name}: {}", exponents.join(", ")));
+    for (curve, color) in curves.iter().zip(COLORS.iter().cycle()) {
+        let (first, last) = bounds(curve.samples.iter().map(|s| s.log().0));
+        let line = [(first, -curve.at(first)), (last, -curve.at(last))];
+        svg.polygon(&line, "none", color, dot / 2.0);
+        for (x, y) in curve.samples.iter().map(Sample::log) {
+            svg.circle(x, -y, dot, color);
+        }
+    }
+    svg.finish()
+}
+
+pub fn run(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    if args.value("day").is_none() {
+        return Err("aoc scale needs --day D".to_string());
+    }
+    let selection = Selection::from_args(args)?;
+    let solution = selection.days[0];
+    let generate = solution
+        .generate
+        .ok_or_else(|| format!("{} has no input generator", solution.name()))?;
+
+    let from = args.number("from")?.unwrap_or(DEFAULT_FROM);
+    let factor = args.number("factor")?.unwrap_or(DEFAULT_FACTOR);
+    let steps = args.number("steps")?.unwrap_or(DEFAULT_STEPS);
+    let runs = args.number("runs")?.unwrap_or(DEFAULT_RUNS);
+    let budget = args.number("budget")?.unwrap_or(DEFAULT_BUDGET);
+    if from == 0 || factor < 2 || steps < 2 || runs == 0 {
+        return Err("--from and --runs must be at least 1, --factor and --steps at least 2".into());
+    }
+    let params = params(args, solution)?;
+
+    let mut curves = Vec::new();
+    for part in selection.parts(solution) {
+        let mut samples = Vec::new();
+        let mut size = Some(from);
+        while let Some(current) = size.filter(|_| samples.len() < steps) {
+            let input = generate(current, SEED);
+            let seconds = time(solution, part, &input, &params, runs)
+                .map_err(|err| format!("{err} (size {current})"))?;
+            samples.push(Sample {
+                size: current,
+                seconds,
+            });
+            // Two sizes at least, for a line to fit
+            size = current
+                .checked_mul(factor)
+                .filter(|_| seconds <= budget || samples.len() < 2);
+        }
+        let fit = fit(&samples).ok_or_else(|| {
+            format!(
+                "{} part {part}: sizes overflow past {from}",
+                solution.name()
+            )
+        })?;
+        curves.push(Curve { part, samples, fit });
+    }
+
+    let write = |out: &mut dyn Write| -> std::io::Result<()> {
+        for curve in &curves {
+            writeln!(out, "{} part {}", solution.name(), curve.part)?;
+            writeln!(out, "| Size | Time | Slope |")?;
+            writeln!(out, "|---|---|---|")?;
+            let previous = std::iter::once(None).chain(curve.samples.iter().map(Some));
+            for (sample, previous) in curve.samples.iter().zip(previous) {
+                // The exponent between this size and the one before
+                let slope = previous.map_or("-".to_string(), |previous| {
+                    let (x, y) = sample.log();
+                    let (px, py) = previous.log();
+                    format!("{:.2}", (y - py) / (x - px))
+                });
+                let time = nanos(sample.seconds * 1e9);
+                writeln!(out, "| {} | {time} | {slope} |", sample.size)?;
+            }
+            writeln!(
+                out,
+                "\nexponent {:.2}: time grows like size^{:.2}\n",
+                curve.fit.0, curve.fit.0
+            )?;
+            write!(out, "{}", plot(curve))?;
+            writeln!(out)?;
+        }
+        Ok(())
+    };
+    write(out).map_err(|err| err.to_string())?;
+
+    if let Some(path) = args.value("svg") {
+        fs::write(path, svg(&solution.name(), &curves)).map_err(|err| format!("{path}: {err}"))?;
+        writeln!(out, "wrote {path}").map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(exponent: f64) -> Curve {
+        let samples: Vec<Sample> = [16, 32, 64, 128]
+            .into_iter()
+            .map(|size| Sample {
+                size,
+                seconds: 1e-6 * (size as f64).powf(exponent),
+            })
+            .collect();
+        let fit = fit(&samples).unwrap();
+        Curve {
+            part: 1,
+            samples,
+            fit,
+        }
+    }
+
+    #[test]
+    fn test_fit() {
+        let (slope, intercept) = curve(2.0).fit;
+        assert!((slope - 2.0).abs() < 1e-9, "{slope}");
+        assert!((intercept - 1e-6f64.ln()).abs() < 1e-9, "{intercept}");
+
+        let one = [Sample {
+            size: 16,
+            seconds: 1.0,
+        }];
+        assert_eq!(fit(&one), None);
+        assert_eq!(fit(&[one[0], one[0]]), None);
+    }
+
+    #[test]
+    fn test_plot() {
+        let plot = plot(&curve(1.0));
+        let lines: Vec<&str> = plot.lines().collect();
+        assert_eq!(lines.len(), PLOT_HEIGHT + 2);
+        assert_eq!(plot.matches('*').count(), 4);
+        // The fastest run is bottom left, the slowest top right
+        assert!(lines[0].starts_with("128.00µs |") && lines[0].ends_with('*'));
+        assert!(lines[PLOT_HEIGHT - 1].starts_with(" 16.00µs |*"));
+        assert!(lines[PLOT_HEIGHT + 1].trim().starts_with("16 "));
+        assert!(lines[PLOT_HEIGHT + 1].ends_with("128"));
+
+        let svg = svg("day99", &[curve(1.0), curve(2.0)]);
+        assert!(svg.contains("<title>day99: part 1 ~ size^1.00, part 1 ~ size^2.00</title>"));
+        assert_eq!(svg.matches("<circle").count(), 8);
+    }
+
+    #[test]
+    fn test_scale() {
+        let output = |args: &str| {
+            let args = Args::parse(args.split_whitespace().map(String::from), &SPEC)?;
+            let mut out = Vec::new();
+            run(&args, &mut out).map(|()| String::from_utf8(out).unwrap())
+        };
+        let report = output("--day 8 --part 2 --from 8 --steps 3 --runs 1").unwrap();
+        assert!(report.starts_with("day08 part 2\n| Size | Time | Slope |\n"));
+        assert!(report.contains("\n| 32 | "));
+        assert!(report.contains("exponent "));
+
+        // Over budget at once: the second size is timed, but none after it
+        let report = output("--day 4 --part 1 --steps 5 --runs 1 --budget 0").unwrap();
+        assert!(report.contains("\n| 32 | ") && !report.contains("\n| 64 | "));
+
+        assert_eq!(output("").unwrap_err(), "aoc scale needs --day D");
+        assert_eq!(
+            output("--day 1").unwrap_err(),
+            "day01 has no input generator"
+        );
+        assert!(output("--day 8 --factor 1").is_err());
+    }
+}
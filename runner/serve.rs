@@ -0,0 +1,537 @@
+// `aoc serve`: a dashboard on localhost
+//
+// A std `TcpListener` on 127.0.0.1 speaking just enough HTTP/1.1 for a
+// browser: GET only, one request per connection. A few worker threads take
+// the accepted connections from a bounded queue, so a slow solver doesn't
+// hold up the page and a burst of clients can't spawn threads without end.
+// Routes:
+//
+//   /                        every day with buttons to run its parts, the
+//                            spatial days' drawings and the bench history
+//   /run?day=D&part=P        the answer for the day's input.txt, as JSON
+//   /svg?day=D               the day's drawing of its input.txt
+//
+// `year=Y` picks another year than the latest, and `input=example` uses
+// the day's test_input.txt instead of its input.txt.
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::json;
+use common::scan;
+use common::solution::{Solution, SolveError, Year};
+use common::svg::escape;
+
+use crate::args::{Args, Spec};
+use crate::bench::{self, History};
+
+pub const SPEC: Spec = Spec {
+    values: &["port", "history", "data-dir"],
+    flags: &[],
+};
+
+const DEFAULT_PORT: u16 = 8025;
+
+/// Bench records shown on the dashboard, newest first
+const HISTORY_ROWS: usize = 20;
+
+/// Longest request head read before giving up on a client
+const MAX_HEAD: usize = 8192;
+
+/// Connections handled at once
+const WORKERS: usize = 4;
+
+/// Accepted connections waiting for a worker; past this, accepting waits
+const QUEUE: usize = 16;
+
+/// How long a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn html(body: String) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body,
+        }
+    }
+
+    fn json(status: u16, body: String) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, format!("{{\"error\":{}}}\n", json::string(message)))
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            422 => "Unprocessable Content",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+/// A decoded `key=value` from a query string
+type Pair = (String, String);
+
+/// `text` with `+` as a space and `%xx` escapes decoded; `None` if an
+/// escape is malformed or the result isn't UTF-8
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = tail
+                    .get(..2)
+                    .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+                bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+                rest = &tail[2..];
+            }
+            byte => bytes.push(byte),
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// The path of `target` and its decoded `key=value` query pairs
+fn split_target(target: &str) -> Result<(&str, Vec<Pair>), Response> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let pairs = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            percent_decode(key)
+                .zip(percent_decode(value))
+                .ok_or_else(|| Response::error(400, &format!("invalid escape in {pair:?}")))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((path, pairs))
+}
+
+/// The request's year, day and input, or the response to send instead
+fn lookup(query: &[Pair]) -> Result<(&'static Year, &'static Solution, scan::Input), Response> {
+    let value = |key: &str| {
+        query
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    };
+    let number = |key: &str| {
+        value(key)
+            .map(|v| {
+                v.parse::<u32>()
+                    .map_err(|_| Response::error(400, &format!("invalid {key}: {v}")))
+            })
+            .transpose()
+    };
+
+    let year = crate::year(number("year")?).map_err(|err| Response::error(404, &err))?;
+    let day = number("day")?.ok_or_else(|| Response::error(400, "day is required"))?;
+    let solution = year
+        .day(day)
+        .ok_or_else(|| Response::error(404, &format!("no day {day} in {}", year.year)))?;
+    let path = match value("input") {
+        None => solution.input_path(),
+        Some("example") => solution.seed_path(),
+        Some(other) => return Err(Response::error(400, &format!("invalid input: {other}"))),
+    };
+    let input = scan::read_input(&path)
+        .map_err(|err| Response::error(404, &format!("{}: {err}", path.display())))?;
+    Ok((year, solution, input))
+}
+
+fn run(query: &[Pair]) -> Result<Response, Response> {
+    let (year, solution, input) = lookup(query)?;
+    let part = query
+        .iter()
+        .find(|(key, _)| key == "part")
+        .and_then(|(_, part)| part.parse::<u32>().ok())
+        .ok_or_else(|| Response::error(400, "part is required"))?;
+    let params = solution
+        .default_params()
+        .map_err(|err| Response::error(500, &err.to_string()))?;
+
+    let start = Instant::now();
+    let answer = solution.run(part, &input, &params);
+    let elapsed = start.elapsed();
+    let answer = answer.map_err(|err| match err {
+        SolveError::UnknownPart => {
+            Response::error(404, &format!("{} has no part {part}", solution.name()))
+        }
+        err => Response::error(422, &err.to_string()),
+    })?;
+    Ok(Response::json(
+        200,
+        format!(
+            "{{\"year\":{},\"day\":{},\"part\":{part},\"answer\":{},\"seconds\":{}}}\n",
+            year.year,
+            solution.day,
+            json::string(&answer),
+            elapsed.as_secs_f64()
+        ),
+    ))
+}
+
+fn svg(query: &[Pair]) -> Result<Response, Response> {
+    let (_, solution, input) = lookup(query)?;
+    let draw = solution
+        .svg
+        .ok_or_else(|| Response::error(404, &format!("{} has no drawing", solution.name())))?;
+    let params = solution
+        .default_params()
+        .map_err(|err| Response::error(500, &err.to_string()))?;
+    let body = draw(&input, &params).map_err(|err| Response::error(422, &err))?;
+    Ok(Response {
+        status: 200,
+        content_type: "image/svg+xml",
+        body,
+    })
+}
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em}\
+table{border-collapse:collapse}td,th{border:1px solid #ccc;padding:.3em .6em}\
+figure{display:inline-block;margin:1em}img{max-width:480px;border:1px solid #ccc}";
+
+/// Runs the part behind a button and shows its answer next to it
+const SCRIPT: &str =
+    "document.querySelectorAll('button[data-run]').forEach(b=>b.onclick=async()=>{\
+const out=b.parentElement.querySelector('output');out.textContent='…';\
+const r=await(await fetch(b.dataset.run)).json();\
+out.textContent=r.error?'error: '+r.error:r.answer+' ('+r.seconds.toFixed(3)+' s)';});";
+
+fn dashboard(history: &Path) -> String {
+    let mut html = String::new();
+    let _ = writeln!(
+        html,
+        "<!doctype html>\n<html><head><meta charset=\"utf-8\"><title>Advent of Code</title>\
+         <style>{STYLE}</style></head><body>"
+    );
+
+    for year in crate::YEARS.iter().rev() {
+        let _ = writeln!(html, "<h1>Advent of Code {}</h1>", year.year);
+        let _ = writeln!(
+            html,
+            "<table><tr><th>Day</th><th>Approach</th><th>Run</th></tr>"
+        );
+        for solution in year.days {
+            let buttons: String = (1..=solution.parts)
+                .map(|part| {
+                    format!(
+                        "<button data-run=\"/run?year={}&amp;day={}&amp;part={part}\">part {part}</button> ",
+                        year.year, solution.day
+                    )
+                })
+                .collect();
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{buttons}<output></output></td></tr>",
+                solution.name(),
+                escape(solution.approach)
+            );
+        }
+        let _ = writeln!(html, "</table>");
+
+        let _ = writeln!(html, "<h2>Drawings</h2>");
+        for solution in year.days.iter().filter(|solution| solution.svg.is_some()) {
+            let _ = writeln!(
+                html,
+                "<figure><img src=\"/svg?year={}&amp;day={}\" alt=\"{name}\">\
+                 <figcaption>{name}</figcaption></figure>",
+                year.year,
+                solution.day,
+                name = solution.name()
+            );
+        }
+    }
+
+    let _ = writeln!(html, "<h2>Benchmark history</h2>");
+    match History::open(history) {
+        Ok(history) if !history.records.is_empty() => {
+            let _ = writeln!(
+                html,
+                "<table><tr><th>Commit</th><th>Year</th><th>Day</th><th>Part</th>\
+                 <th>Median</th><th>Runs</th></tr>"
+            );
+            for record in history.records.iter().rev().take(HISTORY_ROWS) {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2?}</td><td>{}</td></tr>",
+                    escape(&record.commit),
+                    record.year,
+                    record.day,
+                    record.part,
+                    std::time::Duration::from_nanos(record.median() as u64),
+                    record.samples.len()
+                );
+            }
+            let _ = writeln!(html, "</table>");
+        }
+        Ok(_) => {
+            let _ = writeln!(
+                html,
+                "<p>No runs recorded yet; add some with <code>aoc bench --all --record</code>.</p>"
+            );
+        }
+        Err(err) => {
+            let _ = writeln!(html, "<p>{}</p>", escape(&err));
+        }
+    }
+
+    let _ = writeln!(html, "<script>{SCRIPT}</script>\n</body></html>");
+    html
+}
+
+/// The response to one request
+pub fn respond(method: &str, target: &str, history: &Path) -> Response {
+    if method != "GET" {
+        return Response::error(405, "only GET is supported");
+    }
+    let (path, query) = match split_target(target) {
+        Ok(split) => split,
+        Err(err) => return err,
+    };
+    match path {
+        "/" => Response::html(dashboard(history)),
+        "/run" => run(&query).unwrap_or_else(|err| err),
+        "/svg" => svg(&query).unwrap_or_else(|err| err),
+        _ => Response::error(404, &format!("no page {path}")),
+    }
+}
+
+fn handle(stream: TcpStream, history: &Path) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut head = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+        head.push(line);
+        if head.iter().map(String::len).sum::<usize>() > MAX_HEAD {
+            break;
+        }
+    }
+
+    let request = head.first().map(String::as_str).unwrap_or("");
+    let mut words = request.split_whitespace();
+    let response = match (words.next(), words.next()) {
+        (Some(method), Some(target)) => respond(method, target, history),
+        _ => Response::error(400, "malformed request"),
+    };
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.reason(),
+        response.content_type,
+        response.body.len()
+    )?;
+    stream.write_all(response.body.as_bytes())?;
+    stream.flush()
+}
+
+pub struct Server {
+    listener: TcpListener,
+    history: PathBuf,
+}
+
+impl Server {
+    /// Listens on `port` of 127.0.0.1 only; 0 picks a free port
+    pub fn bind(port: u16, history: PathBuf) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        Ok(Self { listener, history })
+    }
+
+    pub fn addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Answers requests on `WORKERS` threads until the listener fails
+    pub fn serve(&self) -> io::Result<()> {
+        let (sender, receiver) = mpsc::sync_channel::<TcpStream>(QUEUE);
+        let receiver = Mutex::new(receiver);
+        // The lock is only held while waiting for a connection, not handling it
+        let next = || receiver.lock().ok()?.recv().ok();
+        thread::scope(|scope| {
+            for _ in 0..WORKERS {
+                scope.spawn(|| {
+                    while let Some(stream) = next() {
+                        if let Err(err) = handle(stream, &self.history) {
+                            eprintln!("aoc serve: {err}");
+                        }
+                    }
+                });
+            }
+            // Returning drops the sender, which stops the workers
+            let sender = sender;
+            for stream in self.listener.incoming() {
+                if sender.send(stream?).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+pub fn serve(args: &Args, out: &mut impl Write) -> Result<(), String> {
+    let port = args.number("port")?.unwrap_or(DEFAULT_PORT);
+    let server = Server::bind(port, bench::history_path(args)).map_err(|err| err.to_string())?;
+    let addr = server.addr().map_err(|err| err.to_string())?;
+    writeln!(out, "serving http://{addr}/").map_err(|err| err.to_string())?;
+    out.flush().map_err(|err| err.to_string())?;
+    server.serve().map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    /// A minimal HTTP client: status, content type and body of `request`
+    fn send(addr: SocketAddr, request: &str) -> (u16, String, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        let content_type = head
+            .lines()
+            .find_map(|line| line.strip_prefix("Content-Type: "))
+            .unwrap()
+            .to_string();
+        assert!(head.contains(&format!("Content-Length: {}", body.len())));
+        (status, content_type, body.to_string())
+    }
+
+    fn get(addr: SocketAddr, target: &str) -> (u16, String, String) {
+        send(
+            addr,
+            &format!("GET {target} HTTP/1.1\r\nHost: localhost\r\n\r\n"),
+        )
+    }
+
+    fn server() -> SocketAddr {
+        let history = std::env::temp_dir().join("aoc-serve-no-history.tsv");
+        let server = Server::bind(0, history).unwrap();
+        let addr = server.addr().unwrap();
+        thread::spawn(move || server.serve());
+        addr
+    }
+
+    #[test]
+    fn test_dashboard() {
+        let addr = server();
+        assert!(addr.ip().is_loopback());
+        let (status, content_type, body) = get(addr, "/");
+        assert_eq!(
+            (status, content_type.as_str()),
+            (200, "text/html; charset=utf-8")
+        );
+        assert!(body.contains("<h1>Advent of Code 2025</h1>"));
+        assert!(body.contains("data-run=\"/run?year=2025&amp;day=7&amp;part=2\""));
+        assert_eq!(body.matches("<img src=\"/svg?").count(), 4);
+        assert!(body.contains("No runs recorded yet"));
+    }
+
+    #[test]
+    fn test_run() {
+        let addr = server();
+        assert_eq!(
+            get(addr, "/run?day=7&part=2&input=example")
+                .2
+                .split(",\"seconds\"")
+                .next(),
+            Some("{\"year\":2025,\"day\":7,\"part\":2,\"answer\":\"18\"")
+        );
+        assert_eq!(
+            get(addr, "/run?day=%37&part=2&input=exam%70le")
+                .2
+                .split(",\"seconds\"")
+                .next(),
+            Some("{\"year\":2025,\"day\":7,\"part\":2,\"answer\":\"18\"")
+        );
+        let (status, content_type, body) = get(addr, "/run?year=2025&day=12&part=2");
+        assert_eq!((status, content_type.as_str()), (404, "application/json"));
+        assert_eq!(body, "{\"error\":\"day12 has no part 2\"}\n");
+        assert_eq!(get(addr, "/run?day=13&part=1").0, 404);
+        assert_eq!(get(addr, "/run?day=x&part=1").0, 400);
+        assert_eq!(get(addr, "/run?day=7").0, 400);
+    }
+
+    #[test]
+    fn test_many_clients() {
+        let addr = server();
+        let clients: Vec<_> = (0..4 * (WORKERS + QUEUE))
+            .map(|_| thread::spawn(move || get(addr, "/run?day=7&part=1&input=example").0))
+            .collect();
+        for client in clients {
+            assert_eq!(client.join().unwrap(), 200);
+        }
+    }
+
+    #[test]
+    fn test_svg() {
+        let addr = server();
+        let (status, content_type, body) = get(addr, "/svg?day=7&input=example");
+        assert_eq!((status, content_type.as_str()), (200, "image/svg+xml"));
+        assert!(body.starts_with("<svg "));
+        assert!(body.contains("<title>13 splits</title>"));
+        assert_eq!(
+            get(addr, "/svg?day=1").2,
+            "{\"error\":\"day01 has no drawing\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let addr = server();
+        assert_eq!(get(addr, "/nowhere").0, 404);
+        assert_eq!(send(addr, "POST / HTTP/1.1\r\n\r\n").0, 405);
+        assert_eq!(send(addr, "\r\n").0, 400);
+        assert_eq!(
+            get(addr, "/run?day=7&part=%zz").2,
+            "{\"error\":\"invalid escape in \\\"part=%zz\\\"\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a+b%2Fc%3d"), Some("a b/c=".to_string()));
+        assert_eq!(percent_decode("%C3%A9"), Some("é".to_string()));
+        assert_eq!(percent_decode("%"), None);
+        assert_eq!(percent_decode("%+1"), None);
+        assert_eq!(percent_decode("%ff"), None);
+    }
+}
@@ -0,0 +1,107 @@
+// Benchmark: std SipHash vs FxHash in the days that hash in their hot loops
+// Run with: cargo bench -p year2025 --bench hash
+//
+// Each workload is the day's real `partN_with_hasher` on its committed
+// input, so parsing is included and the speedups are what the solvers get.
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use common::hash::FxBuildHasher;
+use common::params::Params;
+
+const DAY07: &[u8] = include_bytes!("../../day07/input.txt");
+const DAY10: &[u8] = include_bytes!("../../day10/input.txt");
+const DAY11: &[u8] = include_bytes!("../../day11/input.txt");
+
+/// Name, runs to take the best of, SipHash run, FxHash run
+type Workload = (&'static str, usize, fn() -> i64, fn() -> i64);
+
+fn best_of(runs: usize, mut f: impl FnMut() -> i64) -> Duration {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn defaults(day: u32) -> Params {
+    year2025::YEAR.day(day).unwrap().default_params().unwrap()
+}
+
+fn day07_part1<S: BuildHasher + Default>() -> i64 {
+    day07::part1_with_hasher::<S>(black_box(DAY07)).unwrap() as i64
+}
+
+fn day07_part2<S: BuildHasher + Default>() -> i64 {
+    day07::part2_with_hasher::<S>(black_box(DAY07)).unwrap()
+}
+
+fn day10_part2<S: BuildHasher + Default>() -> i64 {
+    day10::part2_with_hasher::<S>(black_box(DAY10), &defaults(10)).unwrap()
+}
+
+fn day11_part1<S: BuildHasher + Default>() -> i64 {
+    day11::part1_with_hasher::<S>(black_box(DAY11), &defaults(11)).unwrap()
+}
+
+fn day11_part2<S: BuildHasher + Default>() -> i64 {
+    day11::part2_with_hasher::<S>(black_box(DAY11), &defaults(11)).unwrap()
+}
+
+fn main() {
+    // day10 part 2 takes seconds per run, so it gets fewer
+    let workloads: [Workload; 5] = [
+        (
+            "day07 part 1 beam sets",
+            20,
+            day07_part1::<RandomState>,
+            day07_part1::<FxBuildHasher>,
+        ),
+        (
+            "day07 part 2 timeline memo",
+            20,
+            day07_part2::<RandomState>,
+            day07_part2::<FxBuildHasher>,
+        ),
+        (
+            "day10 part 2 pivot maps",
+            3,
+            day10_part2::<RandomState>,
+            day10_part2::<FxBuildHasher>,
+        ),
+        (
+            "day11 part 1 path memo",
+            20,
+            day11_part1::<RandomState>,
+            day11_part1::<FxBuildHasher>,
+        ),
+        (
+            "day11 part 2 path memo",
+            20,
+            day11_part2::<RandomState>,
+            day11_part2::<FxBuildHasher>,
+        ),
+    ];
+
+    println!(
+        "{:<28} {:>14} {:>14} {:>8}",
+        "workload", "SipHash", "FxHash", "speedup"
+    );
+    for (name, runs, sip, fx) in workloads {
+        assert_eq!(sip(), fx(), "{name}: hashers disagree");
+        let (sip, fx) = (best_of(runs, sip), best_of(runs, fx));
+        println!(
+            "{:<28} {:>14.2?} {:>14.2?} {:>7.2}x",
+            name,
+            sip,
+            fx,
+            sip.as_secs_f64() / fx.as_secs_f64()
+        );
+    }
+}
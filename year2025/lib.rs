@@ -0,0 +1,211 @@
+// Advent of Code 2025 - every day's solution, for the runner and the C ABI
+//
+// The day crates stay where they are, next to the other languages' ports;
+// this crate only lists them. Each entry calls the day's `part1`/`part2`, so
+// only the requested part is computed.
+
+use common::solution::{text, Solution, Year};
+
+/// The crate directory of a day, relative to this one
+macro_rules! dir {
+    ($day:literal) => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../", $day)
+    };
+}
+
+const DAYS: &[Solution] = &[
+    Solution {
+        day: 1,
+        parts: 2,
+        dir: dir!("day01"),
+        version: day01::SOLVER_VERSION,
+        defaults: day01::DEFAULTS,
+        units: day01::UNITS,
+        validate: day01::validate,
+        approach: "Dial simulation; clicks past the marks counted in constant time per move",
+        svg: None,
+        generate: None,
+        solve: |part, input, params| match part {
+            1 => text(day01::part1(input, params)),
+            _ => text(day01::part2(input, params)),
+        },
+    },
+    Solution {
+        day: 2,
+        parts: 2,
+        dir: dir!("day02"),
+        version: day02::SOLVER_VERSION,
+        defaults: &[],
+        units: day02::UNITS,
+        validate: day02::validate,
+        approach: "Invalid IDs as pattern × repunit intervals, with Möbius inclusion–exclusion",
+        svg: None,
+        generate: None,
+        solve: |part, input, _| match part {
+            1 => text(day02::part1(input)),
+            _ => text(day02::part2(input)),
+        },
+    },
+    Solution {
+        day: 3,
+        parts: 2,
+        dir: dir!("day03"),
+        version: day03::SOLVER_VERSION,
+        defaults: day03::DEFAULTS,
+        units: day03::UNITS,
+        validate: day03::validate,
+        approach: "Greedy pick of the largest digit that leaves enough for the rest",
+        svg: None,
+        generate: None,
+        solve: |part, input, params| match part {
+            1 => text(day03::part1(input)),
+            _ => text(day03::part2(input, params)),
+        },
+    },
+    Solution {
+        day: 4,
+        parts: 2,
+        dir: dir!("day04"),
+        version: day04::SOLVER_VERSION,
+        defaults: day04::DEFAULTS,
+        units: day04::UNITS,
+        validate: day04::validate,
+        approach: "Neighbour counts; part 2 peels off accessible rolls until none are left",
+        svg: Some(day04::svg),
+        generate: Some(day04::generate),
+        solve: |part, input, params| match part {
+            1 => text(day04::part1(input, params)),
+            _ => text(day04::part2(input, params)),
+        },
+    },
+    Solution {
+        day: 5,
+        parts: 2,
+        dir: dir!("day05"),
+        version: day05::SOLVER_VERSION,
+        defaults: &[],
+        units: day05::UNITS,
+        validate: day05::validate,
+        approach: "Range merging",
+        svg: None,
+        generate: None,
+        solve: |part, input, _| match part {
+            1 => text(day05::part1(input)),
+            _ => text(day05::part2(input)),
+        },
+    },
+    Solution {
+        day: 6,
+        parts: 2,
+        dir: dir!("day06"),
+        version: day06::SOLVER_VERSION,
+        defaults: &[],
+        units: day06::UNITS,
+        validate: day06::validate,
+        approach: "Worksheet split into column groups, read by row or by column",
+        svg: None,
+        generate: None,
+        solve: |part, input, _| match part {
+            1 => text(day06::part1(input)),
+            _ => text(day06::part2(input)),
+        },
+    },
+    Solution {
+        day: 7,
+        parts: 2,
+        dir: dir!("day07"),
+        version: day07::SOLVER_VERSION,
+        defaults: &[],
+        units: day07::UNITS,
+        validate: day07::validate,
+        approach: "Beam sets row by row; memoized timeline count",
+        svg: Some(|input, _| day07::svg(input)),
+        generate: None,
+        solve: |part, input, _| match part {
+            1 => text(day07::part1(input)),
+            _ => text(day07::part2(input)),
+        },
+    },
+    Solution {
+        day: 8,
+        parts: 2,
+        dir: dir!("day08"),
+        version: day08::SOLVER_VERSION,
+        defaults: day08::DEFAULTS,
+        units: day08::UNITS,
+        validate: day08::validate,
+        approach: "Pairs sorted by distance, then union-find",
+        svg: None,
+        generate: Some(day08::generate),
+        solve: |part, input, params| match part {
+            1 => text(day08::part1(input, params)),
+            _ => text(day08::part2(input)),
+        },
+    },
+    Solution {
+        day: 9,
+        parts: 2,
+        dir: dir!("day09"),
+        version: day09::SOLVER_VERSION,
+        defaults: &[],
+        units: day09::UNITS,
+        validate: day09::validate,
+        approach: "All corner pairs; part 2 checks coordinate-compressed points by ray casting",
+        svg: Some(|input, _| day09::svg(input)),
+        generate: Some(day09::generate),
+        solve: |part, input, _| match part {
+            1 => text(day09::part1(input)),
+            _ => text(day09::part2(input)),
+        },
+    },
+    Solution {
+        day: 10,
+        parts: 2,
+        dir: dir!("day10"),
+        version: day10::SOLVER_VERSION,
+        defaults: day10::DEFAULTS,
+        units: day10::UNITS,
+        validate: day10::validate,
+        approach: "GF(2) elimination; fraction-free elimination plus a bounded search over the free variables",
+        svg: None,
+        generate: None,
+        solve: |part, input, params| match part {
+            1 => text(day10::part1(input)),
+            _ => text(day10::part2(input, params)),
+        },
+    },
+    Solution {
+        day: 11,
+        parts: 2,
+        dir: dir!("day11"),
+        version: day11::SOLVER_VERSION,
+        defaults: day11::DEFAULTS,
+        units: day11::UNITS,
+        validate: day11::validate,
+        approach: "Memoized path counting, with a mask of the via nodes seen for part 2",
+        svg: None,
+        generate: None,
+        solve: |part, input, params| match part {
+            1 => text(day11::part1(input, params)),
+            _ => text(day11::part2(input, params)),
+        },
+    },
+    Solution {
+        day: 12,
+        parts: 1,
+        dir: dir!("day12"),
+        version: day12::SOLVER_VERSION,
+        defaults: &[],
+        units: day12::UNITS,
+        validate: day12::validate,
+        approach: "Backtracking polyomino placement after an area check",
+        svg: Some(|input, _| day12::svg(input)),
+        generate: None,
+        solve: |_, input, _| text(day12::part1(input)),
+    },
+];
+
+pub const YEAR: Year = Year {
+    year: 2025,
+    days: DAYS,
+};